@@ -0,0 +1,318 @@
+use crate::callback::{deliver_batch, deliver_batch_item, report_batch_failure};
+use crate::fetcher::Fetchers;
+use crate::provider::{LlmProvider, LlmRequest};
+use anchor_lang::AccountDeserialize;
+use solana_client::{rpc_client::RpcClient, rpc_config::RpcProgramAccountsConfig};
+use solana_llm_oracle::{Batch, BatchCallback, ChatContext, ORACLE_IDENTITY};
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
+use std::{collections::HashMap, error::Error};
+
+// smaller batches are answered right away, the batch api only pays off for larger ones
+const PROVIDER_BATCH_MIN_SIZE: usize = 16;
+
+struct PendingJob {
+    job: String,
+    /// Batch items the job answers, in the order of its prompts.
+    indices: Vec<u16>,
+    prompts: Vec<String>,
+}
+
+/// Batches handed to the provider's batch api that haven't finished yet.
+/// They only live in memory, after a restart the batch is submitted again.
+#[derive(Default)]
+pub struct PendingBatches {
+    jobs: HashMap<Pubkey, PendingJob>,
+}
+
+impl PendingBatches {
+    /// Answers the batch's unfulfilled prompts, through the provider's batch api when
+    /// there are enough of them, otherwise one by one.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn process(
+        &mut self,
+        payer: &Keypair,
+        config_pda: &Pubkey,
+        provider: &dyn LlmProvider,
+        fetchers: &Fetchers,
+        rpc_client: &RpcClient,
+        program_id: &Pubkey,
+        batch_pubkey: &Pubkey,
+        data: Vec<u8>,
+    ) -> Result<(), Box<dyn Error>> {
+        let Ok(batch) = Batch::try_deserialize(&mut data.as_slice()) else {
+            return Ok(());
+        };
+        // every per-item callback updates the batch, so a queued notification can lag behind
+        let batch = fetch_batch(rpc_client, batch_pubkey).unwrap_or(batch);
+        if batch.is_processed
+            || payer.pubkey() != ORACLE_IDENTITY
+            || self.jobs.contains_key(batch_pubkey)
+        {
+            return Ok(());
+        }
+
+        let Some(chat_context) = rpc_client
+            .get_account(&batch.chat_context)
+            .ok()
+            .and_then(|account| ChatContext::try_deserialize(&mut account.data.as_slice()).ok())
+        else {
+            // nothing can answer the batch anymore, failing it lets the user close it
+            report_or_log(
+                rpc_client,
+                payer,
+                program_id,
+                batch_pubkey,
+                "chat context not found",
+            );
+            return Ok(());
+        };
+        let chat_text = match fetchers
+            .resolve(&chat_context.text, chat_context.content_ref.as_ref())
+            .await
+        {
+            Ok(chat_text) => chat_text,
+            Err(e) => {
+                report_or_log(
+                    rpc_client,
+                    payer,
+                    program_id,
+                    batch_pubkey,
+                    &format!("failed to resolve the chat: {}", e),
+                );
+                return Ok(());
+            }
+        };
+
+        log::info!("Processing batch: {:?}", batch_pubkey);
+        let (indices, prompts): (Vec<u16>, Vec<String>) = batch
+            .prompts
+            .iter()
+            .zip(&batch.fulfilled)
+            .enumerate()
+            .filter(|(_, (_, fulfilled))| !**fulfilled)
+            .map(|(index, (prompt, _))| {
+                let prompt = format!(
+                    "{}, {} Answer in at most {} bytes.",
+                    chat_text, prompt, batch.max_response_len
+                );
+                (index as u16, prompt)
+            })
+            .unzip();
+
+        if prompts.len() >= PROVIDER_BATCH_MIN_SIZE {
+            match provider.submit_batch(&prompts).await {
+                Ok(Some(job)) => {
+                    self.jobs.insert(
+                        *batch_pubkey,
+                        PendingJob {
+                            job,
+                            indices,
+                            prompts,
+                        },
+                    );
+                    return Ok(());
+                }
+                Ok(None) => {}
+                Err(e) => log::warn!("Batch api unavailable, answering inline: {:?}", e),
+            }
+        }
+
+        let responses = generate_inline(provider, &prompts).await?;
+        deliver(
+            rpc_client,
+            payer,
+            config_pda,
+            program_id,
+            batch_pubkey,
+            &batch,
+            &indices,
+            &responses,
+        );
+        Ok(())
+    }
+
+    /// Delivers the responses of every finished batch job. Jobs that failed are answered
+    /// inline instead.
+    pub async fn poll_due(
+        &mut self,
+        payer: &Keypair,
+        config_pda: &Pubkey,
+        provider: &dyn LlmProvider,
+        rpc_client: &RpcClient,
+        program_id: &Pubkey,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut done = Vec::new();
+        for (batch_pubkey, pending) in &self.jobs {
+            let Some(batch) =
+                fetch_batch(rpc_client, batch_pubkey).filter(|batch| !batch.is_processed)
+            else {
+                done.push(*batch_pubkey);
+                continue;
+            };
+
+            let responses = match provider.poll_batch(&pending.job).await {
+                Ok(None) => continue,
+                Ok(Some(responses)) if responses.len() == pending.prompts.len() => responses,
+                Ok(Some(_)) | Err(_) => {
+                    log::warn!("Batch job {} failed, answering inline", pending.job);
+                    generate_inline(provider, &pending.prompts).await?
+                }
+            };
+            deliver(
+                rpc_client,
+                payer,
+                config_pda,
+                program_id,
+                batch_pubkey,
+                &batch,
+                &pending.indices,
+                &responses,
+            );
+            done.push(*batch_pubkey);
+        }
+
+        for batch_pubkey in done {
+            self.jobs.remove(&batch_pubkey);
+        }
+        Ok(())
+    }
+
+    /// Picks up batches requested while the oracle was down.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn process_missed(
+        &mut self,
+        payer: &Keypair,
+        config_pda: &Pubkey,
+        provider: &dyn LlmProvider,
+        fetchers: &Fetchers,
+        rpc_client: &RpcClient,
+        program_id: &Pubkey,
+        program_config: &RpcProgramAccountsConfig,
+    ) -> Result<(), Box<dyn Error>> {
+        let batch_accounts =
+            rpc_client.get_program_accounts_with_config(program_id, program_config.clone())?;
+
+        for (pubkey, account) in batch_accounts {
+            self.process(
+                payer,
+                config_pda,
+                provider,
+                fetchers,
+                rpc_client,
+                program_id,
+                &pubkey,
+                account.data,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+}
+
+fn report_or_log(
+    rpc_client: &RpcClient,
+    payer: &Keypair,
+    program_id: &Pubkey,
+    batch_pubkey: &Pubkey,
+    reason: &str,
+) {
+    if let Err(e) = report_batch_failure(rpc_client, payer, program_id, batch_pubkey, reason) {
+        log::error!("Failed to fail batch {:?}: {:?}", batch_pubkey, e);
+    }
+}
+
+fn fetch_batch(rpc_client: &RpcClient, batch_pubkey: &Pubkey) -> Option<Batch> {
+    rpc_client
+        .get_account(batch_pubkey)
+        .ok()
+        .and_then(|account| Batch::try_deserialize(&mut account.data.as_slice()).ok())
+}
+
+async fn generate_inline(
+    provider: &dyn LlmProvider,
+    prompts: &[String],
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut responses = Vec::with_capacity(prompts.len());
+    for prompt in prompts {
+        let request = LlmRequest {
+            prompt,
+            response_schema: None,
+            labels: None,
+        };
+        responses.push(
+            crate::with_retries(|| provider.generate(request))
+                .await?
+                .text,
+        );
+    }
+    Ok(responses)
+}
+
+// a failed callback leaves the remaining items unfulfilled, they're answered again
+// the next time the oracle picks the batch up. Answers are cut down to the batch's
+// `max_response_len`, a batch whose callback still doesn't fit in a transaction, say
+// because of its account metas, is failed instead.
+#[allow(clippy::too_many_arguments)]
+fn deliver(
+    rpc_client: &RpcClient,
+    payer: &Keypair,
+    config_pda: &Pubkey,
+    program_id: &Pubkey,
+    batch_pubkey: &Pubkey,
+    batch: &Batch,
+    indices: &[u16],
+    responses: &[String],
+) {
+    let responses: Vec<String> = responses
+        .iter()
+        .map(|response| {
+            let mut response = response.clone();
+            crate::truncate_response(&mut response, batch.max_response_len as usize);
+            response
+        })
+        .collect();
+    let result = match batch.callback_mode {
+        BatchCallback::Aggregated => deliver_batch(
+            rpc_client,
+            payer,
+            config_pda,
+            program_id,
+            batch_pubkey,
+            batch,
+            &responses,
+        )
+        .map(|fits| (!fits).then(|| "responses don't fit in a transaction".to_string())),
+        BatchCallback::PerItem => indices
+            .iter()
+            .zip(&responses)
+            .filter(|(index, _)| !batch.fulfilled[**index as usize])
+            .map(|(index, response)| {
+                deliver_batch_item(
+                    rpc_client,
+                    payer,
+                    config_pda,
+                    program_id,
+                    batch_pubkey,
+                    batch,
+                    *index,
+                    response,
+                )
+                .map(|fits| {
+                    (!fits)
+                        .then(|| format!("response to item {} doesn't fit in a transaction", index))
+                })
+            })
+            .find(|outcome| !matches!(outcome, Ok(None)))
+            .unwrap_or(Ok(None)),
+    };
+    let result = match result {
+        Ok(Some(reason)) => {
+            report_batch_failure(rpc_client, payer, program_id, batch_pubkey, &reason)
+        }
+        Ok(None) => Ok(()),
+        Err(e) => Err(e),
+    };
+    if let Err(e) = result {
+        log::error!("Failed to call back batch {:?}: {:?}", batch_pubkey, e);
+    }
+}
@@ -0,0 +1,712 @@
+use crate::lookup_tables::LookupTableManager;
+use anchor_lang::{InstructionData, ToAccountMetas, system_program};
+use solana_client::rpc_client::RpcClient;
+use solana_ed25519_program::new_ed25519_instruction_with_signature;
+use solana_llm_oracle::{
+    Attestation, Batch, Inference, OracleNode, ResponseBuffer, Submissions, WorkflowRun,
+};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    compute_budget::ComputeBudgetInstruction,
+    hash::hash,
+    instruction::{AccountMeta, Instruction},
+    message::{AddressLookupTableAccount, VersionedMessage, v0},
+    packet::PACKET_DATA_SIZE,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::Signer,
+    sysvar,
+    transaction::VersionedTransaction,
+};
+use std::error::Error;
+
+const MAX_TX_RETRY_ATTEMPTS: u8 = 3;
+// leaves room for the signature, header and the buffer accounts of a write txn
+const RESPONSE_CHUNK_SIZE: usize = 900;
+const MAX_FAILURE_REASON_LEN: usize = 200;
+// what's left of a transaction for an intermediate workflow response
+const MAX_RECORDED_STEP_LEN: usize = 800;
+
+/// Sends the response to the callback program, inline when it fits in a single
+/// transaction, otherwise through a response buffer written in chunks.
+/// Either way the callback is preceded by the oracle's ed25519 attestation.
+#[allow(clippy::too_many_arguments)]
+pub fn deliver_response(
+    rpc_client: &RpcClient,
+    payer: &Keypair,
+    config_pda: &Pubkey,
+    program_id: &Pubkey,
+    inference_pubkey: &Pubkey,
+    inference: &Inference,
+    response: &[u8],
+    model_id: &str,
+    lookup_tables: &mut LookupTableManager,
+) -> Result<(), Box<dyn Error>> {
+    let attestation_instruction =
+        attest(payer, inference_pubkey, inference.nonce, response, model_id);
+
+    let callback_accounts: Vec<AccountMeta> = inference
+        .callback_account_metas
+        .iter()
+        .map(|meta| AccountMeta {
+            pubkey: meta.pubkey,
+            is_signer: false,
+            is_writable: meta.is_writable,
+        })
+        .collect();
+
+    let lookup_table_accounts = lookup_tables.fetch_tables(rpc_client, &inference.lookup_tables);
+
+    let mut callback_instruction = Instruction {
+        program_id: *program_id,
+        accounts: solana_llm_oracle::accounts::CallbackFromLlm {
+            payer: payer.pubkey(),
+            config: *config_pda,
+            inference: *inference_pubkey,
+            program: inference.callback_program_id,
+            instructions: sysvar::instructions::ID,
+        }
+        .to_account_metas(None),
+        data: solana_llm_oracle::instruction::CallbackFromLlm {
+            response: response.to_vec(),
+            model_id: model_id.to_string(),
+        }
+        .data(),
+    };
+    callback_instruction
+        .accounts
+        .extend(callback_accounts.clone());
+
+    let instructions = [attestation_instruction, callback_instruction];
+    if fits_in_packet(payer, &instructions, &lookup_table_accounts)? {
+        send_transaction_with_budget(
+            rpc_client,
+            payer,
+            &instructions,
+            &lookup_table_accounts,
+            &ComputeBudget::for_callback(inference),
+        )?;
+    } else {
+        log::info!(
+            "Response of {} bytes doesn't fit in one txn, delivering in chunks",
+            response.len()
+        );
+        deliver_in_chunks(
+            rpc_client,
+            payer,
+            config_pda,
+            program_id,
+            inference_pubkey,
+            inference,
+            response,
+            model_id,
+            instructions[0].clone(),
+            callback_accounts,
+            &lookup_table_accounts,
+        )?;
+    }
+
+    lookup_tables.record_usage(
+        [*config_pda, inference.callback_program_id]
+            .into_iter()
+            .chain(
+                inference
+                    .callback_account_metas
+                    .iter()
+                    .map(|meta| meta.pubkey),
+            ),
+    );
+    if let Err(e) = lookup_tables.maintain(rpc_client, payer) {
+        log::warn!("Failed to update lookup tables: {:?}", e);
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn deliver_in_chunks(
+    rpc_client: &RpcClient,
+    payer: &Keypair,
+    config_pda: &Pubkey,
+    program_id: &Pubkey,
+    inference_pubkey: &Pubkey,
+    inference: &Inference,
+    response: &[u8],
+    model_id: &str,
+    attestation_instruction: Instruction,
+    callback_accounts: Vec<AccountMeta>,
+    lookup_table_accounts: &[AddressLookupTableAccount],
+) -> Result<(), Box<dyn Error>> {
+    let response_buffer =
+        write_response_buffer(rpc_client, payer, program_id, inference_pubkey, response)?;
+
+    let mut callback_instruction = Instruction {
+        program_id: *program_id,
+        accounts: solana_llm_oracle::accounts::CallbackFromBuffer {
+            payer: payer.pubkey(),
+            config: *config_pda,
+            inference: *inference_pubkey,
+            response_buffer,
+            program: inference.callback_program_id,
+            instructions: sysvar::instructions::ID,
+        }
+        .to_account_metas(None),
+        data: solana_llm_oracle::instruction::CallbackFromBuffer {
+            model_id: model_id.to_string(),
+        }
+        .data(),
+    };
+    callback_instruction.accounts.extend(callback_accounts);
+
+    send_transaction_with_budget(
+        rpc_client,
+        payer,
+        &[attestation_instruction, callback_instruction],
+        lookup_table_accounts,
+        &ComputeBudget::for_callback(inference),
+    )?;
+    Ok(())
+}
+
+/// Optimistic inferences: writes the response into the buffer and proposes it,
+/// the callback only fires through `finalize_response` after the dispute window.
+pub fn propose_response(
+    rpc_client: &RpcClient,
+    payer: &Keypair,
+    program_id: &Pubkey,
+    inference_pubkey: &Pubkey,
+    inference: &Inference,
+    response: &[u8],
+    model_id: &str,
+) -> Result<(), Box<dyn Error>> {
+    let response_buffer =
+        write_response_buffer(rpc_client, payer, program_id, inference_pubkey, response)?;
+
+    let attestation_instruction =
+        attest(payer, inference_pubkey, inference.nonce, response, model_id);
+    let propose_instruction = Instruction {
+        program_id: *program_id,
+        accounts: solana_llm_oracle::accounts::ProposeResponse {
+            payer: payer.pubkey(),
+            inference: *inference_pubkey,
+            response_buffer,
+            instructions: sysvar::instructions::ID,
+        }
+        .to_account_metas(None),
+        data: solana_llm_oracle::instruction::ProposeResponse {
+            model_id: model_id.to_string(),
+        }
+        .data(),
+    };
+    send_transaction(
+        rpc_client,
+        payer,
+        &[attestation_instruction, propose_instruction],
+        &[],
+    )?;
+    Ok(())
+}
+
+/// Fires the callback of a proposed response whose dispute window passed unchallenged.
+pub fn finalize_response(
+    rpc_client: &RpcClient,
+    payer: &Keypair,
+    config_pda: &Pubkey,
+    program_id: &Pubkey,
+    inference_pubkey: &Pubkey,
+    inference: &Inference,
+    lookup_tables: &mut LookupTableManager,
+) -> Result<(), Box<dyn Error>> {
+    let lookup_table_accounts = lookup_tables.fetch_tables(rpc_client, &inference.lookup_tables);
+
+    let mut finalize_instruction = Instruction {
+        program_id: *program_id,
+        accounts: solana_llm_oracle::accounts::FinalizeResponse {
+            payer: payer.pubkey(),
+            config: *config_pda,
+            inference: *inference_pubkey,
+            response_buffer: response_buffer_address(program_id, inference_pubkey),
+            program: inference.callback_program_id,
+        }
+        .to_account_metas(None),
+        data: solana_llm_oracle::instruction::FinalizeResponse {}.data(),
+    };
+    finalize_instruction
+        .accounts
+        .extend(
+            inference
+                .callback_account_metas
+                .iter()
+                .map(|meta| AccountMeta {
+                    pubkey: meta.pubkey,
+                    is_signer: false,
+                    is_writable: meta.is_writable,
+                }),
+        );
+
+    send_transaction_with_budget(
+        rpc_client,
+        payer,
+        &[finalize_instruction],
+        &lookup_table_accounts,
+        &ComputeBudget::for_callback(inference),
+    )?;
+    Ok(())
+}
+
+pub fn response_buffer_address(program_id: &Pubkey, inference_pubkey: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[ResponseBuffer::seed(), inference_pubkey.as_ref()],
+        program_id,
+    )
+    .0
+}
+
+// leaves the full response in the buffer pda and returns its address
+fn write_response_buffer(
+    rpc_client: &RpcClient,
+    payer: &Keypair,
+    program_id: &Pubkey,
+    inference_pubkey: &Pubkey,
+    response: &[u8],
+) -> Result<Pubkey, Box<dyn Error>> {
+    let response_buffer = response_buffer_address(program_id, inference_pubkey);
+
+    // a previous delivery may have died half way, start from a clean buffer
+    if rpc_client.get_account(&response_buffer).is_ok() {
+        let close_instruction = Instruction {
+            program_id: *program_id,
+            accounts: solana_llm_oracle::accounts::CloseResponseBuffer {
+                payer: payer.pubkey(),
+                inference: *inference_pubkey,
+                response_buffer,
+            }
+            .to_account_metas(None),
+            data: solana_llm_oracle::instruction::CloseResponseBuffer {}.data(),
+        };
+        send_transaction(rpc_client, payer, &[close_instruction], &[])?;
+    }
+
+    let init_instruction = Instruction {
+        program_id: *program_id,
+        accounts: solana_llm_oracle::accounts::InitResponseBuffer {
+            payer: payer.pubkey(),
+            inference: *inference_pubkey,
+            response_buffer,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: solana_llm_oracle::instruction::InitResponseBuffer {
+            total_len: response.len() as u32,
+            response_hash: hash(response).to_bytes(),
+        }
+        .data(),
+    };
+    send_transaction(rpc_client, payer, &[init_instruction], &[])?;
+
+    for (index, chunk) in response.chunks(RESPONSE_CHUNK_SIZE).enumerate() {
+        let write_instruction = Instruction {
+            program_id: *program_id,
+            accounts: solana_llm_oracle::accounts::WriteResponseChunk {
+                payer: payer.pubkey(),
+                response_buffer,
+            }
+            .to_account_metas(None),
+            data: solana_llm_oracle::instruction::WriteResponseChunk {
+                offset: (index * RESPONSE_CHUNK_SIZE) as u32,
+                chunk: chunk.to_vec(),
+            }
+            .data(),
+        };
+        send_transaction(rpc_client, payer, &[write_instruction], &[])?;
+    }
+
+    Ok(response_buffer)
+}
+
+/// Signs `Attestation::message` for the response, `callback_from_llm` checks it
+/// through the instructions sysvar and stores the signature on the inference.
+fn attest(
+    payer: &Keypair,
+    inference_pubkey: &Pubkey,
+    nonce: u64,
+    response: &[u8],
+    model_id: &str,
+) -> Instruction {
+    let message = Attestation::message(
+        inference_pubkey,
+        nonce,
+        &hash(response).to_bytes(),
+        model_id,
+    );
+    let signature = payer.sign_message(&message);
+    new_ed25519_instruction_with_signature(&message, &signature.into(), &payer.pubkey().to_bytes())
+}
+
+fn fits_in_packet(
+    payer: &Keypair,
+    instructions: &[Instruction],
+    lookup_table_accounts: &[AddressLookupTableAccount],
+) -> Result<bool, Box<dyn Error>> {
+    let message = VersionedMessage::V0(v0::Message::try_compile(
+        &payer.pubkey(),
+        &with_compute_budget(instructions, &ComputeBudget::DEFAULT),
+        lookup_table_accounts,
+        Default::default(),
+    )?);
+    // 1 byte signature count + the fee payer signature
+    Ok(1 + 64 + message.serialize().len() <= PACKET_DATA_SIZE)
+}
+
+/// Compute budget instructions prepended to the oracle's transactions.
+pub struct ComputeBudget {
+    unit_limit: u32,
+    unit_price: u64,
+}
+
+impl ComputeBudget {
+    pub const DEFAULT: Self = Self {
+        unit_limit: 300_000,
+        unit_price: 200_000,
+    };
+
+    /// The requester's budget for a transaction that fires its callback, the price never
+    /// goes above the cap the escrowed fee was sized for.
+    pub fn for_callback(inference: &Inference) -> Self {
+        match &inference.callback_budget {
+            Some(budget) => Self {
+                unit_limit: budget.compute_unit_limit,
+                unit_price: budget.priority_fee_cap.min(Self::DEFAULT.unit_price),
+            },
+            None => Self::DEFAULT,
+        }
+    }
+}
+
+fn with_compute_budget(instructions: &[Instruction], budget: &ComputeBudget) -> Vec<Instruction> {
+    [
+        ComputeBudgetInstruction::set_compute_unit_limit(budget.unit_limit),
+        ComputeBudgetInstruction::set_compute_unit_price(budget.unit_price),
+    ]
+    .into_iter()
+    .chain(instructions.iter().cloned())
+    .collect()
+}
+
+pub fn send_transaction(
+    rpc_client: &RpcClient,
+    payer: &Keypair,
+    instructions: &[Instruction],
+    lookup_table_accounts: &[AddressLookupTableAccount],
+) -> Result<Signature, Box<dyn Error>> {
+    send_transaction_with_budget(
+        rpc_client,
+        payer,
+        instructions,
+        lookup_table_accounts,
+        &ComputeBudget::DEFAULT,
+    )
+}
+
+pub fn send_transaction_with_budget(
+    rpc_client: &RpcClient,
+    payer: &Keypair,
+    instructions: &[Instruction],
+    lookup_table_accounts: &[AddressLookupTableAccount],
+    budget: &ComputeBudget,
+) -> Result<Signature, Box<dyn Error>> {
+    let instructions = with_compute_budget(instructions, budget);
+
+    let mut attempts = 0;
+    loop {
+        let recent_blockhash =
+            rpc_client.get_latest_blockhash_with_commitment(CommitmentConfig::processed())?;
+
+        let message = v0::Message::try_compile(
+            &payer.pubkey(),
+            &instructions,
+            lookup_table_accounts,
+            recent_blockhash.0,
+        )?;
+        let transaction = VersionedTransaction::try_new(VersionedMessage::V0(message), &[payer])?;
+
+        match rpc_client.send_and_confirm_transaction(&transaction) {
+            Ok(signature) => {
+                log::info!("Txn Signature: {}\n", signature);
+                return Ok(signature);
+            }
+            Err(e) => {
+                attempts += 1;
+                log::error!(
+                    "Failed to send txn(attempt {}/{}): {:?}",
+                    attempts,
+                    MAX_TX_RETRY_ATTEMPTS,
+                    e
+                );
+                if attempts >= MAX_TX_RETRY_ATTEMPTS {
+                    return Err(Box::new(e));
+                }
+            }
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+/// Adds this oracle's response to a quorum inference. The submission that brings the
+/// quorum to agreement fires the callback, so the callback accounts always go along.
+pub fn submit_response(
+    rpc_client: &RpcClient,
+    payer: &Keypair,
+    config_pda: &Pubkey,
+    program_id: &Pubkey,
+    inference_pubkey: &Pubkey,
+    inference: &Inference,
+    response: &[u8],
+    lookup_tables: &mut LookupTableManager,
+) -> Result<(), Box<dyn Error>> {
+    let lookup_table_accounts = lookup_tables.fetch_tables(rpc_client, &inference.lookup_tables);
+
+    let mut submit_instruction = Instruction {
+        program_id: *program_id,
+        accounts: solana_llm_oracle::accounts::SubmitResponse {
+            oracle: payer.pubkey(),
+            oracle_node: oracle_node_address(program_id, &payer.pubkey()),
+            config: *config_pda,
+            inference: *inference_pubkey,
+            submissions: submissions_address(program_id, inference_pubkey),
+            program: inference.callback_program_id,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: solana_llm_oracle::instruction::SubmitResponse {
+            response: response.to_vec(),
+        }
+        .data(),
+    };
+    submit_instruction
+        .accounts
+        .extend(
+            inference
+                .callback_account_metas
+                .iter()
+                .map(|meta| AccountMeta {
+                    pubkey: meta.pubkey,
+                    is_signer: false,
+                    is_writable: meta.is_writable,
+                }),
+        );
+
+    // the submission that completes the quorum fires the callback
+    send_transaction_with_budget(
+        rpc_client,
+        payer,
+        &[submit_instruction],
+        &lookup_table_accounts,
+        &ComputeBudget::for_callback(inference),
+    )?;
+    Ok(())
+}
+
+pub fn oracle_node_address(program_id: &Pubkey, identity: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[OracleNode::seed(), identity.as_ref()], program_id).0
+}
+
+pub fn submissions_address(program_id: &Pubkey, inference_pubkey: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[Submissions::seed(), inference_pubkey.as_ref()],
+        program_id,
+    )
+    .0
+}
+
+/// Closes the request on-chain as failed, so the requester isn't left waiting.
+pub fn report_failure(
+    rpc_client: &RpcClient,
+    payer: &Keypair,
+    program_id: &Pubkey,
+    inference_pubkey: &Pubkey,
+    reason: &str,
+) -> Result<(), Box<dyn Error>> {
+    log::warn!("Failing inference {:?}: {}", inference_pubkey, reason);
+
+    let fail_instruction = Instruction {
+        program_id: *program_id,
+        accounts: solana_llm_oracle::accounts::FailInference {
+            payer: payer.pubkey(),
+            inference: *inference_pubkey,
+        }
+        .to_account_metas(None),
+        data: solana_llm_oracle::instruction::FailInference {
+            reason: reason.chars().take(MAX_FAILURE_REASON_LEN).collect(),
+        }
+        .data(),
+    };
+    send_transaction(rpc_client, payer, &[fail_instruction], &[])?;
+    Ok(())
+}
+
+pub fn workflow_run_address(program_id: &Pubkey, inference_pubkey: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[WorkflowRun::seed(), inference_pubkey.as_ref()],
+        program_id,
+    )
+    .0
+}
+
+/// Records the hash of an intermediate workflow response for audit, along with the text cut
+/// down to fit the transaction.
+pub fn record_workflow_step(
+    rpc_client: &RpcClient,
+    payer: &Keypair,
+    program_id: &Pubkey,
+    inference_pubkey: &Pubkey,
+    workflow: &Pubkey,
+    step: u8,
+    response: &str,
+) -> Result<(), Box<dyn Error>> {
+    let record_instruction = Instruction {
+        program_id: *program_id,
+        accounts: solana_llm_oracle::accounts::RecordWorkflowStep {
+            payer: payer.pubkey(),
+            inference: *inference_pubkey,
+            workflow: *workflow,
+            workflow_run: workflow_run_address(program_id, inference_pubkey),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: solana_llm_oracle::instruction::RecordWorkflowStep {
+            step,
+            response: cut(response, MAX_RECORDED_STEP_LEN).to_string(),
+            response_hash: hash(response.as_bytes()).to_bytes(),
+        }
+        .data(),
+    };
+    send_transaction(rpc_client, payer, &[record_instruction], &[])?;
+    Ok(())
+}
+
+/// Calls back a single item of a `BatchCallback::PerItem` batch. Batch responses only go
+/// inline, `Ok(false)` means the response doesn't fit in a transaction and nothing was sent.
+#[allow(clippy::too_many_arguments)]
+pub fn deliver_batch_item(
+    rpc_client: &RpcClient,
+    payer: &Keypair,
+    config_pda: &Pubkey,
+    program_id: &Pubkey,
+    batch_pubkey: &Pubkey,
+    batch: &Batch,
+    index: u16,
+    response: &str,
+) -> Result<bool, Box<dyn Error>> {
+    let data = solana_llm_oracle::instruction::CallbackBatchItem {
+        index,
+        response: response.to_string(),
+    }
+    .data();
+    send_batch_callback(
+        rpc_client,
+        payer,
+        config_pda,
+        program_id,
+        batch_pubkey,
+        batch,
+        data,
+    )
+}
+
+/// Calls back every response of a `BatchCallback::Aggregated` batch in one go, `Ok(false)`
+/// means the responses don't fit in a transaction together and nothing was sent.
+pub fn deliver_batch(
+    rpc_client: &RpcClient,
+    payer: &Keypair,
+    config_pda: &Pubkey,
+    program_id: &Pubkey,
+    batch_pubkey: &Pubkey,
+    batch: &Batch,
+    responses: &[String],
+) -> Result<bool, Box<dyn Error>> {
+    let data = solana_llm_oracle::instruction::CallbackBatch {
+        responses: responses.to_vec(),
+    }
+    .data();
+    send_batch_callback(
+        rpc_client,
+        payer,
+        config_pda,
+        program_id,
+        batch_pubkey,
+        batch,
+        data,
+    )
+}
+
+fn send_batch_callback(
+    rpc_client: &RpcClient,
+    payer: &Keypair,
+    config_pda: &Pubkey,
+    program_id: &Pubkey,
+    batch_pubkey: &Pubkey,
+    batch: &Batch,
+    data: Vec<u8>,
+) -> Result<bool, Box<dyn Error>> {
+    // both batch callbacks take the same accounts
+    let mut callback_instruction = Instruction {
+        program_id: *program_id,
+        accounts: solana_llm_oracle::accounts::CallbackBatch {
+            payer: payer.pubkey(),
+            config: *config_pda,
+            batch: *batch_pubkey,
+            program: batch.callback_program_id,
+        }
+        .to_account_metas(None),
+        data,
+    };
+    callback_instruction
+        .accounts
+        .extend(batch.callback_account_metas.iter().map(|meta| AccountMeta {
+            pubkey: meta.pubkey,
+            is_signer: false,
+            is_writable: meta.is_writable,
+        }));
+
+    let instructions = [callback_instruction];
+    if !fits_in_packet(payer, &instructions, &[])? {
+        return Ok(false);
+    }
+    send_transaction(rpc_client, payer, &instructions, &[])?;
+    Ok(true)
+}
+
+/// Closes a batch out as failed, the items not called back yet never will be.
+pub fn report_batch_failure(
+    rpc_client: &RpcClient,
+    payer: &Keypair,
+    program_id: &Pubkey,
+    batch_pubkey: &Pubkey,
+    reason: &str,
+) -> Result<(), Box<dyn Error>> {
+    log::warn!("Failing batch {:?}: {}", batch_pubkey, reason);
+
+    let fail_instruction = Instruction {
+        program_id: *program_id,
+        accounts: solana_llm_oracle::accounts::FailBatch {
+            payer: payer.pubkey(),
+            batch: *batch_pubkey,
+        }
+        .to_account_metas(None),
+        data: solana_llm_oracle::instruction::FailBatch {
+            reason: reason.chars().take(MAX_FAILURE_REASON_LEN).collect(),
+        }
+        .data(),
+    };
+    send_transaction(rpc_client, payer, &[fail_instruction], &[])?;
+    Ok(())
+}
+
+// the longest prefix of `response` within `max_len` bytes that ends on a char boundary
+fn cut(response: &str, max_len: usize) -> &str {
+    let end = (0..=response.len().min(max_len))
+        .rev()
+        .find(|end| response.is_char_boundary(*end))
+        .unwrap_or_default();
+    &response[..end]
+}
@@ -0,0 +1,100 @@
+use crate::callback::{finalize_response, response_buffer_address};
+use crate::lookup_tables::LookupTableManager;
+use anchor_lang::AccountDeserialize;
+use solana_client::rpc_client::RpcClient;
+use solana_llm_oracle::{Inference, ResponseBuffer};
+use solana_sdk::{pubkey::Pubkey, signature::Keypair};
+use std::{collections::HashMap, error::Error};
+
+/// Proposed responses of optimistic inferences, waiting for their dispute window to pass.
+#[derive(Default)]
+pub struct PendingFinalizations {
+    pending: HashMap<Pubkey, u64>,
+}
+
+impl PendingFinalizations {
+    pub fn track(&mut self, inference_pubkey: &Pubkey, nonce: u64) {
+        self.pending.insert(*inference_pubkey, nonce);
+    }
+
+    /// The proposed, unchallenged response of `inference` if there is one.
+    pub fn proposed_buffer(
+        rpc_client: &RpcClient,
+        program_id: &Pubkey,
+        inference_pubkey: &Pubkey,
+        inference: &Inference,
+    ) -> Option<ResponseBuffer> {
+        let account = rpc_client
+            .get_account(&response_buffer_address(program_id, inference_pubkey))
+            .ok()?;
+        ResponseBuffer::try_deserialize(&mut account.data.as_slice())
+            .ok()
+            .filter(|buffer| buffer.nonce == inference.nonce && buffer.dispute_deadline > 0)
+    }
+
+    /// Calls back every proposal whose dispute window passed. Challenged ones are dropped,
+    /// they settle through `resolve_challenge`.
+    pub fn finalize_due(
+        &mut self,
+        rpc_client: &RpcClient,
+        payer: &Keypair,
+        config_pda: &Pubkey,
+        program_id: &Pubkey,
+        lookup_tables: &mut LookupTableManager,
+    ) -> Result<(), Box<dyn Error>> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        let slot = rpc_client.get_slot()?;
+
+        let mut done = Vec::new();
+        for (inference_pubkey, nonce) in &self.pending {
+            let inference = rpc_client
+                .get_account(inference_pubkey)
+                .ok()
+                .and_then(|account| Inference::try_deserialize(&mut account.data.as_slice()).ok());
+            let Some(inference) =
+                inference.filter(|inference| !inference.is_processed && inference.nonce == *nonce)
+            else {
+                done.push(*inference_pubkey);
+                continue;
+            };
+            let Some(buffer) =
+                Self::proposed_buffer(rpc_client, program_id, inference_pubkey, &inference)
+            else {
+                done.push(*inference_pubkey);
+                continue;
+            };
+
+            if buffer.is_challenged {
+                log::warn!(
+                    "Response to {:?} is challenged, waiting for the resolution",
+                    inference_pubkey
+                );
+                done.push(*inference_pubkey);
+                continue;
+            }
+            if slot <= buffer.dispute_deadline {
+                continue;
+            }
+
+            if let Err(e) = finalize_response(
+                rpc_client,
+                payer,
+                config_pda,
+                program_id,
+                inference_pubkey,
+                &inference,
+                lookup_tables,
+            ) {
+                log::error!("Failed to finalize {:?}: {:?}", inference_pubkey, e);
+            }
+            done.push(*inference_pubkey);
+        }
+
+        for inference_pubkey in done {
+            self.pending.remove(&inference_pubkey);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::{Engine, prelude::BASE64_STANDARD};
+use solana_client::{
+    pubsub_client::PubsubClient,
+    rpc_config::{RpcTransactionLogsConfig, RpcTransactionLogsFilter},
+};
+use solana_llm_oracle::events::{
+    BatchFailed, BatchFulfilled, BatchRequested, ChatCreated, InferenceCancelled, InferenceFailed,
+    InferenceFulfilled, InferenceRequested, PauseUpdated, ScheduleRan, WorkflowStepRecorded,
+};
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+use std::{error::Error, fmt::Debug};
+
+/// Logs every event the program emits, from the program logs of its transactions.
+pub fn spawn_event_logger(websocket_url: &str, program_id: &Pubkey) -> Result<(), Box<dyn Error>> {
+    let (subscription, updates) = PubsubClient::logs_subscribe(
+        websocket_url,
+        RpcTransactionLogsFilter::Mentions(vec![program_id.to_string()]),
+        RpcTransactionLogsConfig {
+            commitment: Some(CommitmentConfig::confirmed()),
+        },
+    )?;
+
+    std::thread::spawn(move || {
+        // dropping the subscription closes the socket
+        let _subscription = subscription;
+        for update in updates {
+            if update.value.err.is_some() {
+                continue;
+            }
+            for data in update
+                .value
+                .logs
+                .iter()
+                .filter_map(|line| line.strip_prefix("Program data: "))
+            {
+                log_event(data);
+            }
+        }
+    });
+    Ok(())
+}
+
+fn log_event(encoded: &str) {
+    let Ok(data) = BASE64_STANDARD.decode(encoded) else {
+        return;
+    };
+
+    let _ = log_if::<ChatCreated>(&data, "Chat created")
+        || log_if::<InferenceRequested>(&data, "Inference requested")
+        || log_if::<InferenceFulfilled>(&data, "Inference fulfilled")
+        || log_if::<InferenceFailed>(&data, "Inference failed")
+        || log_if::<InferenceCancelled>(&data, "Inference cancelled")
+        || log_if::<WorkflowStepRecorded>(&data, "Workflow step recorded")
+        || log_if::<BatchRequested>(&data, "Batch requested")
+        || log_if::<BatchFulfilled>(&data, "Batch fulfilled")
+        || log_if::<BatchFailed>(&data, "Batch failed")
+        || log_if::<ScheduleRan>(&data, "Schedule ran")
+        || log_if::<PauseUpdated>(&data, "Pause updated");
+}
+
+fn log_if<T: Discriminator + AnchorDeserialize + Debug>(data: &[u8], name: &str) -> bool {
+    let Some(payload) = data.strip_prefix(T::DISCRIMINATOR) else {
+        return false;
+    };
+    match T::deserialize(&mut &payload[..]) {
+        Ok(event) => log::info!("{}: {:?}", name, event),
+        Err(e) => log::warn!("Failed to decode {} event: {:?}", name, e),
+    }
+    true
+}
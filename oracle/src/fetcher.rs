@@ -0,0 +1,275 @@
+use reqwest::{Client, Url, redirect::Policy};
+use solana_llm_oracle::{ContentRef, ResponseSchema};
+use solana_sdk::hash::hash;
+use std::{
+    env,
+    error::Error,
+    future::Future,
+    net::{IpAddr, SocketAddr},
+    path::PathBuf,
+    pin::Pin,
+};
+use tokio::{fs::File, io::AsyncReadExt, net::lookup_host};
+
+// prompts are for the llm, anything bigger than this is not a prompt
+const MAX_CONTENT_SIZE: usize = 1024 * 1024;
+
+pub type FetchFuture<'a> = Pin<Box<dyn Future<Output = Result<Vec<u8>, Box<dyn Error>>> + 'a>>;
+
+/// A source the oracle can load off-chain prompt content from.
+pub trait ContentFetcher {
+    /// Whether this fetcher handles the scheme of `uri`.
+    fn supports(&self, uri: &str) -> bool;
+
+    fn fetch<'a>(&'a self, uri: &'a str) -> FetchFuture<'a>;
+}
+
+/// Serves `file://` uris, only from inside `CONTENT_DIR` so requesters can't read the oracle's own files.
+pub struct FileFetcher {
+    root: PathBuf,
+}
+
+impl ContentFetcher for FileFetcher {
+    fn supports(&self, uri: &str) -> bool {
+        uri.starts_with("file://")
+    }
+
+    fn fetch<'a>(&'a self, uri: &'a str) -> FetchFuture<'a> {
+        Box::pin(async move {
+            let path = self
+                .root
+                .join(uri.trim_start_matches("file://").trim_start_matches('/'))
+                .canonicalize()?;
+            if !path.starts_with(self.root.canonicalize()?) {
+                return Err(format!("{} is outside of the content directory", uri).into());
+            }
+
+            // the size is checked before reading, and the read is bounded in case the file grows
+            let too_large = || format!("{} is larger than {} bytes", uri, MAX_CONTENT_SIZE);
+            let file = File::open(path).await?;
+            if file.metadata().await?.len() > MAX_CONTENT_SIZE as u64 {
+                return Err(too_large().into());
+            }
+            let mut content = Vec::new();
+            file.take(MAX_CONTENT_SIZE as u64 + 1)
+                .read_to_end(&mut content)
+                .await?;
+            if content.len() > MAX_CONTENT_SIZE {
+                return Err(too_large().into());
+            }
+            Ok(content)
+        })
+    }
+}
+
+/// Serves `http(s)://` uris, only from public hosts so requesters can't reach the oracle's own network.
+pub struct HttpFetcher;
+
+impl HttpFetcher {
+    // resolves the host up front and pins the connection to the checked addresses, so a second
+    // lookup can't point it anywhere else. Redirects aren't followed for the same reason
+    async fn public_client(url: &Url) -> Result<Client, Box<dyn Error>> {
+        let host = url
+            .host_str()
+            .ok_or_else(|| format!("{} has no host", url))?;
+        let port = url
+            .port_or_known_default()
+            .ok_or_else(|| format!("{} has no port", url))?;
+        let addrs: Vec<SocketAddr> = lookup_host((host.trim_matches(['[', ']']), port))
+            .await?
+            .collect();
+        if addrs.is_empty() || !addrs.iter().all(|addr| is_public(addr.ip())) {
+            return Err(format!("{} doesn't resolve to a public address", host).into());
+        }
+
+        let client = Client::builder()
+            .resolve_to_addrs(host, &addrs)
+            .redirect(Policy::none())
+            .build()?;
+        Ok(client)
+    }
+}
+
+impl ContentFetcher for HttpFetcher {
+    fn supports(&self, uri: &str) -> bool {
+        uri.starts_with("http://") || uri.starts_with("https://")
+    }
+
+    fn fetch<'a>(&'a self, uri: &'a str) -> FetchFuture<'a> {
+        Box::pin(async move {
+            let url = Url::parse(uri)?;
+            let client = Self::public_client(&url).await?;
+            let mut response = client.get(url).send().await?.error_for_status()?;
+            if response.status().is_redirection() {
+                return Err(format!("{} redirects, point at the content itself", uri).into());
+            }
+
+            // the length header is only a hint, the body is counted as it comes in
+            let too_large = || format!("{} is larger than {} bytes", uri, MAX_CONTENT_SIZE);
+            if response
+                .content_length()
+                .is_some_and(|len| len as usize > MAX_CONTENT_SIZE)
+            {
+                return Err(too_large().into());
+            }
+            let mut content = Vec::new();
+            while let Some(chunk) = response.chunk().await? {
+                if content.len() + chunk.len() > MAX_CONTENT_SIZE {
+                    return Err(too_large().into());
+                }
+                content.extend_from_slice(&chunk);
+            }
+            Ok(content)
+        })
+    }
+}
+
+// rules out loopback, private, link-local, shared and other addresses that aren't on the internet
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                || ip.is_multicast()
+                || a == 0
+                || (a == 100 && (b & 0xc0) == 64)
+                || (a == 192 && b == 0))
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public(IpAddr::V4(ip)),
+            None => {
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_multicast()
+                    || ip.is_unique_local()
+                    || ip.is_unicast_link_local())
+            }
+        },
+    }
+}
+
+pub struct Fetchers {
+    fetchers: Vec<Box<dyn ContentFetcher>>,
+}
+
+impl Fetchers {
+    pub fn new() -> Self {
+        let mut fetchers: Vec<Box<dyn ContentFetcher>> = vec![Box::new(HttpFetcher)];
+        if let Ok(root) = env::var("CONTENT_DIR") {
+            fetchers.push(Box::new(FileFetcher { root: root.into() }));
+        }
+        Self { fetchers }
+    }
+
+    /// Inline text unless the account points at off-chain content.
+    pub async fn resolve(
+        &self,
+        text: &str,
+        content_ref: Option<&ContentRef>,
+    ) -> Result<String, Box<dyn Error>> {
+        match content_ref {
+            Some(content_ref) => self.fetch_verified(content_ref).await,
+            None => Ok(text.to_string()),
+        }
+    }
+
+    /// The schema as json, fetched and verified first when it's a reference.
+    pub async fn resolve_schema(
+        &self,
+        response_schema: &ResponseSchema,
+    ) -> Result<serde_json::Value, Box<dyn Error>> {
+        let schema = match response_schema {
+            ResponseSchema::Inline { schema } => schema.clone(),
+            ResponseSchema::Ref { content_ref } => self.fetch_verified(content_ref).await?,
+        };
+        Ok(serde_json::from_str(&schema)?)
+    }
+
+    /// Loads the referenced content and checks it against the on-chain hash before
+    /// handing it out, so nothing unverified ever reaches the prompt.
+    pub async fn fetch_verified(&self, content_ref: &ContentRef) -> Result<String, Box<dyn Error>> {
+        let fetcher = self
+            .fetchers
+            .iter()
+            .find(|fetcher| fetcher.supports(&content_ref.uri))
+            .ok_or_else(|| format!("no fetcher for {}", content_ref.uri))?;
+
+        let content = fetcher.fetch(&content_ref.uri).await?;
+        if content.len() > MAX_CONTENT_SIZE {
+            return Err(format!(
+                "{} is larger than {} bytes",
+                content_ref.uri, MAX_CONTENT_SIZE
+            )
+            .into());
+        }
+        if hash(&content).to_bytes() != content_ref.sha256 {
+            return Err(format!("content hash mismatch for {}", content_ref.uri).into());
+        }
+
+        Ok(String::from_utf8(content)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_public_addr(addr: &str) -> bool {
+        is_public(addr.parse().unwrap())
+    }
+
+    #[test]
+    fn is_public_rules_out_internal_v4() {
+        assert!(is_public_addr("93.184.216.34"));
+        for addr in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "192.0.0.8",
+            "255.255.255.255",
+        ] {
+            assert!(!is_public_addr(addr), "{} counted as public", addr);
+        }
+        assert!(is_public_addr("100.128.0.1"));
+    }
+
+    #[test]
+    fn is_public_rules_out_internal_v6() {
+        assert!(is_public_addr("2606:2800:220:1::1"));
+        for addr in [
+            "::1",
+            "::",
+            "fd00::1",
+            "fe80::1",
+            "ff02::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public_addr(addr), "{} counted as public", addr);
+        }
+        assert!(is_public_addr("::ffff:93.184.216.34"));
+    }
+
+    #[tokio::test]
+    async fn file_fetcher_caps_the_size() {
+        let root = env::temp_dir().join(format!("oracle-content-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("small.txt"), b"prompt").unwrap();
+        std::fs::write(root.join("large.txt"), vec![0; MAX_CONTENT_SIZE + 1]).unwrap();
+        let fetcher = FileFetcher { root: root.clone() };
+
+        assert_eq!(fetcher.fetch("file://small.txt").await.unwrap(), b"prompt");
+        assert!(fetcher.fetch("file://large.txt").await.is_err());
+        assert!(fetcher.fetch("file://../etc/passwd").await.is_err());
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
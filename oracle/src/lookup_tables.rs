@@ -0,0 +1,164 @@
+use solana_address_lookup_table_interface::{
+    instruction::{create_lookup_table, extend_lookup_table},
+    state::{AddressLookupTable, LOOKUP_TABLE_MAX_ADDRESSES},
+};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig, instruction::Instruction,
+    message::AddressLookupTableAccount, pubkey::Pubkey, signature::Keypair, signer::Signer,
+    transaction::Transaction,
+};
+use std::{collections::HashMap, env, error::Error, str::FromStr};
+
+// an account seen in this many callbacks is worth a slot in the oracle's own table
+const HOT_ACCOUNT_THRESHOLD: u32 = 3;
+// keeps the extend txn comfortably under the packet limit
+const MAX_EXTEND_ADDRESSES: usize = 20;
+
+/// Tracks which callback accounts the oracle keeps seeing and moves them into
+/// lookup tables owned by the oracle, so callbacks stay under the packet limit.
+pub struct LookupTableManager {
+    managed: Vec<Pubkey>,
+    usage: HashMap<Pubkey, u32>,
+}
+
+impl LookupTableManager {
+    /// Tables created by earlier runs are passed back in through `ORACLE_LOOKUP_TABLES` (comma separated).
+    pub fn from_env() -> Self {
+        let managed = env::var("ORACLE_LOOKUP_TABLES")
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|key| Pubkey::from_str(key.trim()).ok())
+            .collect();
+
+        Self {
+            managed,
+            usage: HashMap::new(),
+        }
+    }
+
+    pub fn record_usage(&mut self, keys: impl IntoIterator<Item = Pubkey>) {
+        for key in keys {
+            *self.usage.entry(key).or_default() += 1;
+        }
+    }
+
+    /// Requester named tables first, then the oracle's own. Tables that can't be
+    /// loaded are skipped, the message compiler only keeps the ones it needs anyway.
+    pub fn fetch_tables(
+        &self,
+        rpc_client: &RpcClient,
+        requested: &[Pubkey],
+    ) -> Vec<AddressLookupTableAccount> {
+        requested
+            .iter()
+            .chain(self.managed.iter().filter(|key| !requested.contains(key)))
+            .filter_map(|key| match fetch_table(rpc_client, key) {
+                Ok(addresses) => Some(AddressLookupTableAccount {
+                    key: *key,
+                    addresses,
+                }),
+                Err(e) => {
+                    log::warn!("Skipping lookup table {}: {:?}", key, e);
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Moves hot accounts that aren't in any managed table yet into one,
+    /// creating a new table when all of ours are full.
+    pub fn maintain(
+        &mut self,
+        rpc_client: &RpcClient,
+        payer: &Keypair,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut hot: Vec<Pubkey> = self
+            .usage
+            .iter()
+            .filter(|(_, count)| **count >= HOT_ACCOUNT_THRESHOLD)
+            .map(|(key, _)| *key)
+            .collect();
+
+        if hot.is_empty() {
+            return Ok(());
+        }
+
+        let mut target = None;
+        for table in self.managed.iter() {
+            let addresses = fetch_table(rpc_client, table)?;
+            hot.retain(|key| !addresses.contains(key));
+            if target.is_none() && addresses.len() < LOOKUP_TABLE_MAX_ADDRESSES {
+                target = Some((*table, LOOKUP_TABLE_MAX_ADDRESSES - addresses.len()));
+            }
+        }
+
+        if hot.is_empty() {
+            return Ok(());
+        }
+
+        let (table, room) = match target {
+            Some(target) => target,
+            None => (
+                self.create_table(rpc_client, payer)?,
+                LOOKUP_TABLE_MAX_ADDRESSES,
+            ),
+        };
+
+        hot.truncate(room.min(MAX_EXTEND_ADDRESSES));
+        let extend_instruction =
+            extend_lookup_table(table, payer.pubkey(), Some(payer.pubkey()), hot.clone());
+        send_instruction(rpc_client, payer, extend_instruction)?;
+
+        for key in hot.iter() {
+            self.usage.remove(key);
+        }
+        log::info!(
+            "Extended lookup table {} with {} accounts",
+            table,
+            hot.len()
+        );
+        Ok(())
+    }
+
+    fn create_table(
+        &mut self,
+        rpc_client: &RpcClient,
+        payer: &Keypair,
+    ) -> Result<Pubkey, Box<dyn Error>> {
+        let recent_slot = rpc_client.get_slot_with_commitment(CommitmentConfig::finalized())?;
+        let (create_instruction, table) =
+            create_lookup_table(payer.pubkey(), payer.pubkey(), recent_slot);
+        send_instruction(rpc_client, payer, create_instruction)?;
+
+        self.managed.push(table);
+        log::info!(
+            "Created lookup table {}, add it to ORACLE_LOOKUP_TABLES to keep it across restarts",
+            table
+        );
+        Ok(table)
+    }
+}
+
+fn fetch_table(rpc_client: &RpcClient, key: &Pubkey) -> Result<Vec<Pubkey>, Box<dyn Error>> {
+    let account = rpc_client.get_account(key)?;
+    let table = AddressLookupTable::deserialize(&account.data)?;
+    Ok(table.addresses.to_vec())
+}
+
+fn send_instruction(
+    rpc_client: &RpcClient,
+    payer: &Keypair,
+    instruction: Instruction,
+) -> Result<(), Box<dyn Error>> {
+    let recent_blockhash =
+        rpc_client.get_latest_blockhash_with_commitment(CommitmentConfig::processed())?;
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhash.0,
+    );
+    rpc_client.send_and_confirm_transaction(&transaction)?;
+    Ok(())
+}
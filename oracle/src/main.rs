@@ -1,42 +1,76 @@
-use crate::types::{ApiResponse, Content, Part, RequestBody};
-use anchor_lang::{AccountDeserialize, AnchorSerialize, Discriminator};
+use crate::batch::PendingBatches;
+use crate::callback::{
+    deliver_response, oracle_node_address, propose_response, report_failure, submissions_address,
+    submit_response,
+};
+use crate::disputes::PendingFinalizations;
+use crate::fetcher::Fetchers;
+use crate::lookup_tables::LookupTableManager;
+use crate::provider::{Gemini, LlmProvider, LlmRequest, ProviderFuture};
+use crate::reveals::PendingReveals;
+use anchor_lang::{AccountDeserialize, Discriminator};
 use dotenvy::dotenv;
 use log::Level;
 use reqwest::Client;
 use solana_account_decoder::UiAccountEncoding;
 use solana_client::{
-    pubsub_client::PubsubClient,
+    pubsub_client::{ProgramSubscription, PubsubClient},
     rpc_client::RpcClient,
     rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
     rpc_filter::{Memcmp, RpcFilterType},
+    rpc_response::{Response, RpcKeyedAccount},
 };
 use solana_sdk::{
-    commitment_config::CommitmentConfig,
-    compute_budget::ComputeBudgetInstruction,
-    instruction::{AccountMeta, Instruction},
-    pubkey::Pubkey,
-    signature::Keypair,
-    signer::Signer,
-    transaction::Transaction,
+    commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Keypair, signer::Signer,
 };
-use std::{env, error::Error, str::FromStr, vec};
+use std::{env, error::Error, str::FromStr, time::Duration, vec};
 use tokio::sync::mpsc;
 use tokio_stream::{StreamExt, wrappers::ReceiverStream};
 
+mod batch;
+mod callback;
+mod disputes;
+mod events;
+mod fetcher;
+mod lookup_tables;
+mod output;
+mod provider;
+mod reveals;
+mod schedules;
 mod types;
+mod workflow;
 
-const MAX_TX_RETRY_ATTEMPTS: u8 = 3;
 const MAX_API_RETRY_ATTEMPTS: u8 = 3;
+// answers that don't match the response schema are asked for again this many times in total
+const MAX_SCHEMA_ATTEMPTS: u8 = 2;
+const MODEL_ID: &str = "gemini-2.5-flash";
+const EMBEDDING_MODEL_ID: &str = "gemini-embedding-001";
+// how often pending reveals and proposed responses are checked for their slot windows
+const WINDOW_POLL_INTERVAL: Duration = Duration::from_secs(2);
+// how often batch jobs submitted to the provider are checked for completion
+const BATCH_POLL_INTERVAL: Duration = Duration::from_secs(30);
+// how often schedules are checked for a due run
+const SCHEDULE_POLL_INTERVAL: Duration = Duration::from_secs(10);
+// how often the config is checked for a pause, and how often a paused oracle checks for the resume
+const PAUSE_POLL_INTERVAL: Duration = Duration::from_secs(10);
 
 #[tokio::main]
 async fn main() {
     dotenv().ok();
     simple_logger::init_with_level(Level::Info).unwrap();
     let (api_key, rpc_url, websocket_url, payer, config_pda, program_id) = load_config();
+    let mut lookup_tables = LookupTableManager::from_env();
+    let mut pending_reveals = PendingReveals::default();
+    let mut pending_finalizations = PendingFinalizations::default();
+    let mut pending_batches = PendingBatches::default();
 
     log::info!(" Oracle identity: {:?}", payer.pubkey());
     log::info!(" RPC: {:?}", rpc_url.as_str());
     log::info!(" WS: {:?}", websocket_url.as_str());
+    // started once, it outlives oracle restarts
+    if let Err(e) = events::spawn_event_logger(websocket_url.as_str(), &program_id) {
+        log::warn!("Failed to subscribe to program events: {:?}", e);
+    }
     loop {
         if let Err(e) = run_oracle(
             rpc_url.as_str(),
@@ -45,6 +79,10 @@ async fn main() {
             &payer,
             &config_pda,
             &program_id,
+            &mut lookup_tables,
+            &mut pending_reveals,
+            &mut pending_finalizations,
+            &mut pending_batches,
         )
         .await
         {
@@ -53,6 +91,7 @@ async fn main() {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn run_oracle(
     rpc_url: &str,
     websocket_url: &str,
@@ -60,71 +99,125 @@ async fn run_oracle(
     payer: &Keypair,
     config_pda: &Pubkey,
     program_id: &Pubkey,
+    lookup_tables: &mut LookupTableManager,
+    pending_reveals: &mut PendingReveals,
+    pending_finalizations: &mut PendingFinalizations,
+    pending_batches: &mut PendingBatches,
 ) -> Result<(), Box<dyn Error>> {
     let client: Client = Client::new();
+    let fetchers = Fetchers::new();
+    let provider = Gemini::new(client, api_key, MODEL_ID, EMBEDDING_MODEL_ID);
 
     let rpc_client = RpcClient::new_with_commitment(rpc_url, CommitmentConfig::processed());
+    wait_while_paused(&rpc_client, config_pda).await?;
 
     let (tx, rx) = mpsc::channel(100);
     let mut stream = ReceiverStream::new(rx);
 
-    let rpc_config = RpcAccountInfoConfig {
-        commitment: Some(CommitmentConfig::processed()),
-        encoding: Some(UiAccountEncoding::Base64),
-        ..Default::default()
-    };
-
-    let filters = vec![RpcFilterType::Memcmp(Memcmp::new(
-        0,
-        solana_client::rpc_filter::MemcmpEncodedBytes::Bytes(
-            solana_llm_oracle::Inference::DISCRIMINATOR.to_vec(),
-        ),
-    ))];
-
-    let program_config = RpcProgramAccountsConfig {
-        account_config: rpc_config,
-        filters: Some(filters),
-        ..Default::default()
-    };
+    let program_config = accounts_config(solana_llm_oracle::Inference::DISCRIMINATOR);
+    let batch_config = accounts_config(solana_llm_oracle::Batch::DISCRIMINATOR);
+    let schedule_config = accounts_config(solana_llm_oracle::Schedule::DISCRIMINATOR);
 
     process_missed_inferences(
         payer,
         config_pda,
-        &client,
-        api_key,
+        &provider,
+        &fetchers,
         &rpc_client,
         program_id,
         &program_config,
+        lookup_tables,
+        pending_reveals,
+        pending_finalizations,
     )
     .await?;
+    pending_batches
+        .process_missed(
+            payer,
+            config_pda,
+            &provider,
+            &fetchers,
+            &rpc_client,
+            program_id,
+            &batch_config,
+        )
+        .await?;
 
-    let subscription =
-        PubsubClient::program_subscribe(websocket_url, program_id, Some(program_config))?;
-
-    tokio::spawn(async move {
-        for update in subscription.1 {
-            if tx.send(update).await.is_err() {
-                log::error!("Receiver dropped");
-                break;
+    forward_updates(
+        PubsubClient::program_subscribe(websocket_url, program_id, Some(program_config))?,
+        tx.clone(),
+    );
+    forward_updates(
+        PubsubClient::program_subscribe(websocket_url, program_id, Some(batch_config))?,
+        tx,
+    );
+
+    let mut window_ticker = tokio::time::interval(WINDOW_POLL_INTERVAL);
+    let mut batch_ticker = tokio::time::interval(BATCH_POLL_INTERVAL);
+    let mut schedule_ticker = tokio::time::interval(SCHEDULE_POLL_INTERVAL);
+    let mut pause_ticker = tokio::time::interval(PAUSE_POLL_INTERVAL);
+    loop {
+        let update = tokio::select! {
+            update = stream.next() => match update {
+                Some(update) => update,
+                None => break,
+            },
+            _ = window_ticker.tick() => {
+                pending_reveals.reveal_due(&rpc_client, payer, config_pda, program_id, lookup_tables)?;
+                pending_finalizations.finalize_due(&rpc_client, payer, config_pda, program_id, lookup_tables)?;
+                continue;
             }
-        }
-    });
-
-    while let Some(update) = stream.next().await {
-        if let Ok(inference_pubkey) = Pubkey::from_str(&update.value.pubkey) {
-            if let Some(data) = update.value.account.data.decode() {
+            _ = batch_ticker.tick() => {
+                pending_batches.poll_due(payer, config_pda, &provider, &rpc_client, program_id).await?;
+                continue;
+            }
+            _ = schedule_ticker.tick() => {
+                schedules::run_due_schedules(&rpc_client, payer, config_pda, program_id, &schedule_config)?;
+                continue;
+            }
+            _ = pause_ticker.tick() => {
+                // restarting waits out the pause, then picks up whatever was requested meanwhile
+                if callbacks_paused(&rpc_client, config_pda) {
+                    return Ok(());
+                }
+                continue;
+            }
+        };
+
+        if let Ok(pubkey) = Pubkey::from_str(&update.value.pubkey) {
+            let Some(data) = update.value.account.data.decode() else {
+                continue;
+            };
+            if data.starts_with(solana_llm_oracle::Batch::DISCRIMINATOR) {
+                pending_batches
+                    .process(
+                        payer,
+                        config_pda,
+                        &provider,
+                        &fetchers,
+                        &rpc_client,
+                        program_id,
+                        &pubkey,
+                        data,
+                    )
+                    .await?;
+                log::info!("batch pda: {:?}", pubkey);
+            } else {
                 process_inference(
-                    &payer,
-                    &config_pda,
-                    &client,
-                    api_key,
+                    payer,
+                    config_pda,
+                    &provider,
+                    &fetchers,
                     &rpc_client,
-                    &inference_pubkey,
+                    &pubkey,
                     data,
                     program_id,
+                    lookup_tables,
+                    pending_reveals,
+                    pending_finalizations,
                 )
                 .await?;
-                log::info!("inference pda: {:?}", inference_pubkey);
+                log::info!("inference pda: {:?}", pubkey);
             }
         }
     }
@@ -132,14 +225,92 @@ async fn run_oracle(
     Ok(())
 }
 
+pub fn load_config_account(
+    rpc_client: &RpcClient,
+    config_pda: &Pubkey,
+) -> Result<solana_llm_oracle::Config, Box<dyn Error>> {
+    let account = rpc_client.get_account(config_pda)?;
+    Ok(solana_llm_oracle::Config::try_deserialize(
+        &mut account.data.as_slice(),
+    )?)
+}
+
+// a config that can't be loaded, say one still in the legacy layout before `migrate_config`,
+// rejects every callback just the same
+fn callbacks_paused(rpc_client: &RpcClient, config_pda: &Pubkey) -> bool {
+    match load_config_account(rpc_client, config_pda) {
+        Ok(config) => config.callbacks_paused,
+        Err(e) => {
+            log::warn!("Failed to load the config, treating it as paused: {:?}", e);
+            true
+        }
+    }
+}
+
+// nothing the oracle answers could be delivered while callbacks are paused
+async fn wait_while_paused(
+    rpc_client: &RpcClient,
+    config_pda: &Pubkey,
+) -> Result<(), Box<dyn Error>> {
+    let mut logged = false;
+    while callbacks_paused(rpc_client, config_pda) {
+        if !logged {
+            log::warn!("Callbacks are paused, idling until they resume");
+            logged = true;
+        }
+        tokio::time::sleep(PAUSE_POLL_INTERVAL).await;
+    }
+    Ok(())
+}
+
+fn accounts_config(discriminator: &[u8]) -> RpcProgramAccountsConfig {
+    let rpc_config = RpcAccountInfoConfig {
+        commitment: Some(CommitmentConfig::processed()),
+        encoding: Some(UiAccountEncoding::Base64),
+        ..Default::default()
+    };
+
+    let filters = vec![RpcFilterType::Memcmp(Memcmp::new(
+        0,
+        solana_client::rpc_filter::MemcmpEncodedBytes::Bytes(discriminator.to_vec()),
+    ))];
+
+    RpcProgramAccountsConfig {
+        account_config: rpc_config,
+        filters: Some(filters),
+        ..Default::default()
+    }
+}
+
+// the subscription's receiver blocks, so it gets a thread of its own
+fn forward_updates(
+    (subscription, updates): ProgramSubscription,
+    tx: mpsc::Sender<Response<RpcKeyedAccount>>,
+) {
+    tokio::task::spawn_blocking(move || {
+        // dropping the subscription closes the socket
+        let _subscription = subscription;
+        for update in updates {
+            if tx.blocking_send(update).is_err() {
+                log::error!("Receiver dropped");
+                break;
+            }
+        }
+    });
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn process_missed_inferences(
     payer: &Keypair,
     config_pda: &Pubkey,
-    client: &Client,
-    api_key: &str,
+    provider: &dyn LlmProvider,
+    fetchers: &Fetchers,
     rpc_client: &RpcClient,
     program_id: &Pubkey,
     program_config: &RpcProgramAccountsConfig,
+    lookup_tables: &mut LookupTableManager,
+    pending_reveals: &mut PendingReveals,
+    pending_finalizations: &mut PendingFinalizations,
 ) -> Result<(), Box<dyn Error>> {
     let inference_accounts =
         rpc_client.get_program_accounts_with_config(program_id, program_config.clone())?;
@@ -148,139 +319,206 @@ async fn process_missed_inferences(
         process_inference(
             payer,
             config_pda,
-            client,
-            api_key,
+            provider,
+            fetchers,
             rpc_client,
             &pubkey,
             account.data,
             program_id,
+            lookup_tables,
+            pending_reveals,
+            pending_finalizations,
         )
         .await?;
     }
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn process_inference(
     payer: &Keypair,
     config_pda: &Pubkey,
-    client: &Client,
-    api_key: &str,
+    provider: &dyn LlmProvider,
+    fetchers: &Fetchers,
     rpc_client: &RpcClient,
     inference_pubkey: &Pubkey,
     data: Vec<u8>,
     program_id: &Pubkey,
+    lookup_tables: &mut LookupTableManager,
+    pending_reveals: &mut PendingReveals,
+    pending_finalizations: &mut PendingFinalizations,
 ) -> Result<(), Box<dyn Error>> {
     if let Ok(inference) =
         solana_llm_oracle::Inference::try_deserialize_unchecked(&mut data.as_slice())
     {
-        if inference.is_processed == true {
+        if inference.is_processed || !inference.is_ready {
+            return Ok(());
+        }
+        if !should_serve(rpc_client, payer, program_id, inference_pubkey, &inference) {
+            return Ok(());
+        }
+        // already proposed, only the dispute window is left to wait out
+        let proposed = (inference.dispute_slots > 0)
+            .then(|| {
+                PendingFinalizations::proposed_buffer(
+                    rpc_client,
+                    program_id,
+                    inference_pubkey,
+                    &inference,
+                )
+            })
+            .flatten();
+        if let Some(buffer) = proposed {
+            if !buffer.is_challenged {
+                pending_finalizations.track(inference_pubkey, inference.nonce);
+            }
             return Ok(());
         }
 
         log::info!("Processing inference: {:?}", inference_pubkey);
 
-        if let Ok(chat_context_data) = rpc_client.get_account(&inference.chat_context) {
-            if let Ok(chat_context) = solana_llm_oracle::ChatContext::try_deserialize_unchecked(
+        if let Ok(chat_context_data) = rpc_client.get_account(&inference.chat_context)
+            && let Ok(chat_context) = solana_llm_oracle::ChatContext::try_deserialize_unchecked(
                 &mut chat_context_data.data.as_slice(),
-            ) {
-                log::info!("processing inference data: {:?}", inference);
-                let prompt = format!("{}, {}", chat_context.text, inference.text);
-
-                let mut ai_response = String::new();
-                let mut api_attempt = 0;
-
-                while api_attempt < MAX_API_RETRY_ATTEMPTS {
-                    match llm_inference(client, &api_key, prompt.as_str()).await {
-                        Ok(response) => {
-                            ai_response = response;
-                            break;
-                        }
-                        Err(e) => {
-                            api_attempt += 1;
-                            log::error!(
-                                "Ai inference Failed(attempt {}/{}): {:?}",
-                                api_attempt,
-                                MAX_API_RETRY_ATTEMPTS,
-                                e
-                            );
+            )
+        {
+            if !chat_context.is_ready {
+                log::warn!(
+                    "Chat context {:?} is still being uploaded, skipping inference",
+                    inference.chat_context
+                );
+                return Ok(());
+            }
 
-                            if api_attempt >= MAX_API_RETRY_ATTEMPTS {
-                                return Err(e);
-                            }
-                        }
-                    }
+            log::info!("processing inference data: {:?}", inference);
+
+            let texts = tokio::join!(
+                fetchers.resolve(&chat_context.text, chat_context.content_ref.as_ref()),
+                fetchers.resolve(&inference.text, inference.content_ref.as_ref()),
+            );
+            let (chat_text, inference_text) = match texts {
+                (Ok(chat_text), Ok(inference_text)) => (chat_text, inference_text),
+                (Err(e), _) | (_, Err(e)) => {
+                    return fail_or_skip(
+                        rpc_client,
+                        payer,
+                        program_id,
+                        inference_pubkey,
+                        &e.to_string(),
+                    );
                 }
-
-                let response_data = [
-                    solana_llm_oracle::instruction::CallbackFromLlm::DISCRIMINATOR.to_vec(),
-                    ai_response.try_to_vec()?,
-                ]
-                .concat();
-
-                let mut callback_instruction = Instruction {
-                    program_id: *program_id,
-                    accounts: vec![
-                        AccountMeta::new(payer.pubkey(), true),
-                        AccountMeta::new_readonly(*config_pda, false),
-                        AccountMeta::new(*inference_pubkey, false),
-                        AccountMeta::new_readonly(inference.callback_program_id, false),
-                    ],
-                    data: response_data,
-                };
-
-                let remaining_accounts: Vec<AccountMeta> = inference
-                    .callback_account_metas
-                    .iter()
-                    .map(|meta| AccountMeta {
-                        pubkey: meta.pubkey,
-                        is_signer: false,
-                        is_writable: meta.is_writable,
-                    })
-                    .collect();
-
-                callback_instruction.accounts.extend(remaining_accounts);
-
-                let mut attempts = 0;
-                while attempts < MAX_TX_RETRY_ATTEMPTS {
-                    if let Ok(recent_blockhash) = rpc_client
-                        .get_latest_blockhash_with_commitment(CommitmentConfig::processed())
-                    {
-                        let compute_budget_instruction =
-                            ComputeBudgetInstruction::set_compute_unit_limit(300_000);
-                        let priority_fee_instruction =
-                            ComputeBudgetInstruction::set_compute_unit_price(200_000);
-
-                        let transaction = Transaction::new_signed_with_payer(
-                            &[
-                                compute_budget_instruction,
-                                priority_fee_instruction,
-                                callback_instruction.clone(),
-                            ],
-                            Some(&payer.pubkey()),
-                            &[payer],
-                            recent_blockhash.0,
-                        );
-
-                        match rpc_client.send_and_confirm_transaction(&transaction) {
-                            Ok(signature) => {
-                                log::info!("Txn Signature: {}\n", signature);
-                                break;
-                            }
-                            Err(e) => {
-                                attempts += 1;
-                                log::error!(
-                                    "Failed to send txn(attempt {}/{}): {:?}",
-                                    attempts,
-                                    MAX_TX_RETRY_ATTEMPTS,
-                                    e
-                                );
-                                if attempts >= MAX_TX_RETRY_ATTEMPTS {
-                                    return Err(Box::new(e));
-                                }
-                            }
+            };
+            // the workflow's last step stands in for the inference text
+            let inference_text = match &inference.workflow {
+                // recording the steps is up to the main oracle, the program keeps
+                // workflows out of quorums but older requests may still carry both
+                Some(_) if payer.pubkey() != solana_llm_oracle::ORACLE_IDENTITY => {
+                    log::warn!(
+                        "Skipping workflow inference {:?}, only the main oracle runs workflows",
+                        inference_pubkey
+                    );
+                    return Ok(());
+                }
+                Some(workflow) => {
+                    let last_step = workflow::run_workflow(
+                        provider,
+                        rpc_client,
+                        payer,
+                        program_id,
+                        inference_pubkey,
+                        workflow,
+                        &chat_text,
+                        &inference_text,
+                    )
+                    .await?;
+                    match last_step {
+                        Ok(prompt) => prompt,
+                        Err(reason) => {
+                            return fail_or_skip(
+                                rpc_client,
+                                payer,
+                                program_id,
+                                inference_pubkey,
+                                &reason,
+                            );
                         }
                     }
                 }
+                None => inference_text,
+            };
+
+            let response = match &inference.embedding {
+                Some(embedding) => embed(provider, embedding, &inference_text).await?,
+                None => {
+                    complete(
+                        provider,
+                        fetchers,
+                        &inference,
+                        &chat_context,
+                        &chat_text,
+                        &inference_text,
+                    )
+                    .await?
+                }
+            };
+            let response = match response {
+                Ok(response) => response,
+                Err(reason) => {
+                    return fail_or_skip(rpc_client, payer, program_id, inference_pubkey, &reason);
+                }
+            };
+            let model_id = match inference.embedding {
+                Some(_) => provider.embedding_model_id(),
+                None => provider.model_id(),
+            };
+
+            let commit_reveal = inference
+                .quorum
+                .as_ref()
+                .is_some_and(|quorum| quorum.commit_reveal.is_some());
+            if commit_reveal {
+                pending_reveals.commit(
+                    rpc_client,
+                    payer,
+                    program_id,
+                    inference_pubkey,
+                    &inference,
+                    response,
+                )?;
+            } else if inference.quorum.is_some() {
+                submit_response(
+                    rpc_client,
+                    payer,
+                    config_pda,
+                    program_id,
+                    inference_pubkey,
+                    &inference,
+                    &response,
+                    lookup_tables,
+                )?;
+            } else if inference.dispute_slots > 0 {
+                propose_response(
+                    rpc_client,
+                    payer,
+                    program_id,
+                    inference_pubkey,
+                    &inference,
+                    &response,
+                    model_id,
+                )?;
+                pending_finalizations.track(inference_pubkey, inference.nonce);
+            } else {
+                deliver_response(
+                    rpc_client,
+                    payer,
+                    config_pda,
+                    program_id,
+                    inference_pubkey,
+                    &inference,
+                    &response,
+                    model_id,
+                    lookup_tables,
+                )?;
             }
         }
     }
@@ -288,42 +526,229 @@ async fn process_inference(
     Ok(())
 }
 
-async fn llm_inference(
-    client: &Client,
-    api_key: &str,
-    text: &str,
-) -> Result<String, Box<dyn Error>> {
-    let request_body = RequestBody {
-        contents: vec![Content {
-            parts: vec![Part {
-                text: text.to_string(),
-            }],
-        }],
+// only the main oracle identity can fail an inference, the other quorum oracles leave it
+// to the main one rather than having their transaction rejected and retrying forever
+fn fail_or_skip(
+    rpc_client: &RpcClient,
+    payer: &Keypair,
+    program_id: &Pubkey,
+    inference_pubkey: &Pubkey,
+    reason: &str,
+) -> Result<(), Box<dyn Error>> {
+    if payer.pubkey() != solana_llm_oracle::ORACLE_IDENTITY {
+        log::warn!(
+            "Not answering quorum inference {:?}, leaving its failure to the main oracle: {}",
+            inference_pubkey,
+            reason
+        );
+        return Ok(());
+    }
+    report_failure(rpc_client, payer, program_id, inference_pubkey, reason)
+}
+
+// quorum inferences go to every registered oracle that hasn't answered this request yet,
+// everything else only to the main oracle identity
+fn should_serve(
+    rpc_client: &RpcClient,
+    payer: &Keypair,
+    program_id: &Pubkey,
+    inference_pubkey: &Pubkey,
+    inference: &solana_llm_oracle::Inference,
+) -> bool {
+    if inference.quorum.is_none() {
+        return payer.pubkey() == solana_llm_oracle::ORACLE_IDENTITY;
+    }
+
+    let oracle_node = rpc_client
+        .get_account(&oracle_node_address(program_id, &payer.pubkey()))
+        .ok()
+        .and_then(|account| {
+            solana_llm_oracle::OracleNode::try_deserialize(&mut account.data.as_slice()).ok()
+        });
+    match oracle_node {
+        Some(oracle_node) if oracle_node.stake >= solana_llm_oracle::MIN_ORACLE_STAKE => {}
+        Some(_) => {
+            log::warn!(
+                "Stake below the minimum, skipping quorum inference {:?}",
+                inference_pubkey
+            );
+            return false;
+        }
+        None => {
+            log::warn!(
+                "Not a registered oracle, skipping quorum inference {:?}",
+                inference_pubkey
+            );
+            return false;
+        }
+    }
+
+    if let Some(windows) = inference
+        .quorum
+        .as_ref()
+        .and_then(|quorum| quorum.commit_reveal)
+    {
+        let commit_deadline = inference.requested_slot + windows.commit_slots;
+        if rpc_client
+            .get_slot()
+            .map_or(true, |slot| slot > commit_deadline)
+        {
+            return false;
+        }
+    }
+
+    match rpc_client.get_account(&submissions_address(program_id, inference_pubkey)) {
+        Ok(account) => solana_llm_oracle::Submissions::try_deserialize(
+            &mut account.data.as_slice(),
+        )
+        .map_or(true, |submissions| {
+            submissions.nonce != inference.nonce
+                || !submissions
+                    .entries
+                    .iter()
+                    .any(|entry| entry.oracle == payer.pubkey())
+        }),
+        Err(_) => true,
+    }
+}
+
+/// Runs the prompt through the model and shapes the answer the way the requester asked for,
+/// `Err` holds the reason to fail the inference with.
+async fn complete(
+    provider: &dyn LlmProvider,
+    fetchers: &Fetchers,
+    inference: &solana_llm_oracle::Inference,
+    chat_context: &solana_llm_oracle::ChatContext,
+    chat_text: &str,
+    inference_text: &str,
+) -> Result<Result<Vec<u8>, String>, Box<dyn Error>> {
+    let mut prompt = format!("{}, {}", chat_text, inference_text);
+    if let Some(output_type) = &inference.output_type {
+        prompt = format!("{} {}", prompt, output::format_hint(output_type));
+    }
+
+    // an inference's own output format wins over the agent's schema
+    let schema = match inference
+        .response_schema
+        .as_ref()
+        .or(chat_context.response_schema.as_ref())
+        .filter(|_| inference.output_type.is_none())
+    {
+        Some(response_schema) => match fetchers.resolve_schema(response_schema).await {
+            Ok(schema) => Some(schema),
+            Err(e) => return Ok(Err(format!("invalid response schema: {}", e))),
+        },
+        None => None,
+    };
+
+    let labels = match &inference.output_type {
+        Some(solana_llm_oracle::OutputType::Classification { labels }) => Some(labels.as_slice()),
+        _ => None,
     };
 
-    let url =
-        "https://generativelanguage.googleapis.com/v1beta/models/gemini-2.5-flash:generateContent";
-
-    let response = client
-        .post(url)
-        .header("Content-Type", "application/json")
-        .header("x-goog-api-key", api_key)
-        .json(&request_body)
-        .send()
-        .await;
-
-    match response {
-        Ok(res) => {
-            let api_res: ApiResponse = res.json().await?;
-            let text = api_res.candidates[0].content.parts[0].text.clone();
-            log::info!("ai res: {}", text);
-            Ok(text)
+    let mut ai_response = String::new();
+    let mut probability = None;
+    let mut attempt_prompt = prompt.clone();
+    for schema_attempt in 1..=MAX_SCHEMA_ATTEMPTS {
+        let request = LlmRequest {
+            prompt: &attempt_prompt,
+            response_schema: schema.as_ref(),
+            labels,
+        };
+        let generation = with_retries(|| provider.generate(request)).await?;
+        ai_response = generation.text;
+        probability = generation.probability;
+
+        let Some(schema) = &schema else {
+            break;
+        };
+        let Err(e) = check_schema(schema, &ai_response) else {
+            break;
+        };
+        log::warn!(
+            "Response doesn't match the schema(attempt {}/{}): {}",
+            schema_attempt,
+            MAX_SCHEMA_ATTEMPTS,
+            e
+        );
+        if schema_attempt >= MAX_SCHEMA_ATTEMPTS {
+            return Ok(Err(format!("response doesn't match the schema: {}", e)));
         }
-        Err(e) => {
-            log::error!("Failed to get ai response: {}", e);
-            Err(Box::new(e))
+        attempt_prompt = format!(
+            "{}\n\nYour previous answer {} didn't match the response schema: {}. Answer again.",
+            prompt, ai_response, e
+        );
+    }
+
+    Ok(match &inference.output_type {
+        Some(output_type) => output::coerce(output_type, &ai_response, probability),
+        // cutting json short would break it, fail instead
+        None if schema.is_some() && ai_response.len() > inference.max_response_len as usize => {
+            Err("structured response exceeds the requester's limit".to_string())
+        }
+        None => {
+            truncate_response(&mut ai_response, inference.max_response_len as usize);
+            Ok(ai_response.into_bytes())
         }
+    })
+}
+
+/// Embeds the inference text and quantizes the vector for the callback.
+async fn embed(
+    provider: &dyn LlmProvider,
+    embedding: &solana_llm_oracle::EmbeddingConfig,
+    text: &str,
+) -> Result<Result<Vec<u8>, String>, Box<dyn Error>> {
+    let values = with_retries(|| provider.embed(text, embedding.max_dimensions)).await?;
+    Ok(output::quantize(embedding, &values))
+}
+
+// the program rejects anything over the requester's limit, so cut at the closest char boundary
+fn truncate_response(response: &mut String, max_len: usize) {
+    if response.len() <= max_len {
+        return;
+    }
+
+    let mut end = max_len;
+    while !response.is_char_boundary(end) {
+        end -= 1;
     }
+    log::warn!(
+        "Response of {} bytes exceeds the requester's limit of {}, truncating",
+        response.len(),
+        max_len
+    );
+    response.truncate(end);
+}
+
+async fn with_retries<'a, T>(
+    call: impl Fn() -> ProviderFuture<'a, T>,
+) -> Result<T, Box<dyn Error>> {
+    let mut api_attempt = 0;
+    loop {
+        match call().await {
+            Ok(response) => return Ok(response),
+            Err(e) => {
+                api_attempt += 1;
+                log::error!(
+                    "Ai inference Failed(attempt {}/{}): {:?}",
+                    api_attempt,
+                    MAX_API_RETRY_ATTEMPTS,
+                    e
+                );
+
+                if api_attempt >= MAX_API_RETRY_ATTEMPTS {
+                    return Err(e);
+                }
+            }
+        }
+    }
+}
+
+fn check_schema(schema: &serde_json::Value, response: &str) -> Result<(), String> {
+    let value: serde_json::Value =
+        serde_json::from_str(response).map_err(|e| format!("not json, {}", e))?;
+    jsonschema::validate(schema, &value).map_err(|e| e.to_string())
 }
 
 fn load_config() -> (String, String, String, Keypair, Pubkey, Pubkey) {
@@ -0,0 +1,238 @@
+use half::f16;
+use solana_llm_oracle::{EmbeddingConfig, OutputType, Quantization};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+// enum mode only returns the label, without logprobs there's nothing to tell how sure the model is
+const UNKNOWN_CONFIDENCE_BPS: u16 = 0;
+
+/// Tells the model what shape of answer the requester expects, appended to the prompt.
+pub fn format_hint(output_type: &OutputType) -> String {
+    match output_type {
+        OutputType::U8 { min, max } => {
+            format!("Answer with a single integer from {} to {}.", min, max)
+        }
+        OutputType::U64 { min, max } => {
+            format!("Answer with a single integer from {} to {}.", min, max)
+        }
+        OutputType::I64 { min, max } => {
+            format!("Answer with a single integer from {} to {}.", min, max)
+        }
+        OutputType::Bool => "Answer with only true or false.".to_string(),
+        OutputType::Label { labels } | OutputType::Classification { labels } => {
+            format!("Answer with exactly one of: {}.", labels.join(", "))
+        }
+        OutputType::Pubkey => "Answer with a single base58 Solana address.".to_string(),
+    }
+}
+
+/// Coerces the model output into the borsh encoding of `output_type`, tolerating
+/// stray text around the value. The program checks the same range again before the callback.
+/// Classifications take their confidence from `probability` when the provider reported it,
+/// and `UNKNOWN_CONFIDENCE_BPS` otherwise.
+pub fn coerce(
+    output_type: &OutputType,
+    text: &str,
+    probability: Option<f64>,
+) -> Result<Vec<u8>, String> {
+    let text = text.trim();
+    match output_type {
+        OutputType::U8 { min, max } => {
+            let value: u8 = find_integer(text)?;
+            in_range(value, *min, *max)?;
+            Ok(vec![value])
+        }
+        OutputType::U64 { min, max } => {
+            let value: u64 = find_integer(text)?;
+            in_range(value, *min, *max)?;
+            Ok(value.to_le_bytes().to_vec())
+        }
+        OutputType::I64 { min, max } => {
+            let value: i64 = find_integer(text)?;
+            in_range(value, *min, *max)?;
+            Ok(value.to_le_bytes().to_vec())
+        }
+        OutputType::Bool => {
+            let words: Vec<String> = tokens(text).map(str::to_lowercase).collect();
+            let is_true = words.iter().any(|word| word == "true" || word == "yes");
+            let is_false = words.iter().any(|word| word == "false" || word == "no");
+            match (is_true, is_false) {
+                (true, false) => Ok(vec![1]),
+                (false, true) => Ok(vec![0]),
+                _ => Err(format!("expected true or false, got {:?}", text)),
+            }
+        }
+        OutputType::Label { labels } => Ok(vec![find_label(labels, text)?]),
+        OutputType::Pubkey => tokens(text)
+            .find_map(|token| Pubkey::from_str(token).ok())
+            .map(|pubkey| pubkey.to_bytes().to_vec())
+            .ok_or_else(|| format!("expected an address, got {:?}", text)),
+        OutputType::Classification { labels } => {
+            let index = find_label(labels, text)?;
+            let confidence_bps = probability.map_or(UNKNOWN_CONFIDENCE_BPS, |probability| {
+                (probability.clamp(0.0, 1.0) * 10_000.0).round() as u16
+            });
+            let mut value = vec![index];
+            value.extend_from_slice(&confidence_bps.to_le_bytes());
+            Ok(value)
+        }
+    }
+}
+
+fn find_label(labels: &[String], text: &str) -> Result<u8, String> {
+    // an exact answer wins, otherwise the one label mentioned in the text as whole words,
+    // so "no" isn't found in "know"
+    let exact = labels
+        .iter()
+        .position(|label| label.eq_ignore_ascii_case(text.trim_matches(is_noise)));
+    let words = lowercase_words(text);
+    let mentioned: Vec<usize> = labels
+        .iter()
+        .enumerate()
+        .filter(|(_, label)| {
+            let label_words = lowercase_words(label);
+            !label_words.is_empty()
+                && words
+                    .windows(label_words.len())
+                    .any(|window| window == label_words.as_slice())
+        })
+        .map(|(index, _)| index)
+        .collect();
+    match (exact, mentioned.as_slice()) {
+        (Some(index), _) | (None, &[index]) => Ok(index as u8),
+        _ => Err(format!("expected one of {:?}, got {:?}", labels, text)),
+    }
+}
+
+fn lowercase_words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+fn is_noise(c: char) -> bool {
+    !c.is_alphanumeric() && c != '-'
+}
+
+fn tokens(text: &str) -> impl Iterator<Item = &str> {
+    text.split_whitespace()
+        .map(|token| token.trim_matches(is_noise))
+        .filter(|token| !token.is_empty())
+}
+
+fn find_integer<T: FromStr>(text: &str) -> Result<T, String> {
+    tokens(text)
+        .find_map(|token| token.parse().ok())
+        .ok_or_else(|| format!("expected an integer, got {:?}", text))
+}
+
+fn in_range<T: PartialOrd + std::fmt::Display>(value: T, min: T, max: T) -> Result<(), String> {
+    if value < min || value > max {
+        return Err(format!("{} is outside of {}..={}", value, min, max));
+    }
+    Ok(())
+}
+
+/// Quantizes an embedding into the borsh encoded vector the callback expects, cut to the
+/// requested dimensions. i8 values are scaled by the largest component, which keeps the
+/// direction and so the cosine similarity.
+pub fn quantize(embedding: &EmbeddingConfig, values: &[f32]) -> Result<Vec<u8>, String> {
+    let values = &values[..values.len().min(embedding.max_dimensions as usize)];
+    if values.is_empty() {
+        return Err("the provider returned an empty embedding".to_string());
+    }
+    let mut encoded = (values.len() as u32).to_le_bytes().to_vec();
+    match embedding.quantization {
+        Quantization::I8 => {
+            let scale = values.iter().fold(0f32, |max, value| max.max(value.abs()));
+            let scale = if scale > 0.0 { 127.0 / scale } else { 0.0 };
+            encoded.extend(
+                values
+                    .iter()
+                    .map(|value| (value * scale).round() as i8 as u8),
+            );
+        }
+        Quantization::F16 => {
+            for value in values {
+                encoded.extend_from_slice(&f16::from_f32(*value).to_bits().to_le_bytes());
+            }
+        }
+    }
+    Ok(encoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(labels: &[&str]) -> Vec<String> {
+        labels.iter().map(|label| label.to_string()).collect()
+    }
+
+    #[test]
+    fn coerce_finds_integers_in_range() {
+        let output_type = OutputType::U8 { min: 1, max: 10 };
+        assert_eq!(coerce(&output_type, "Score: 7.", None), Ok(vec![7]));
+        assert!(coerce(&output_type, "11", None).is_err());
+        assert!(coerce(&output_type, "seven", None).is_err());
+
+        let output_type = OutputType::I64 { min: -5, max: 5 };
+        assert_eq!(
+            coerce(&output_type, "-3", None),
+            Ok((-3i64).to_le_bytes().to_vec())
+        );
+    }
+
+    #[test]
+    fn coerce_needs_an_unambiguous_bool() {
+        assert_eq!(coerce(&OutputType::Bool, "Yes.", None), Ok(vec![1]));
+        assert_eq!(coerce(&OutputType::Bool, "false", None), Ok(vec![0]));
+        assert!(coerce(&OutputType::Bool, "yes and no", None).is_err());
+    }
+
+    #[test]
+    fn coerce_scales_classification_confidence() {
+        let output_type = OutputType::Classification {
+            labels: labels(&["spam", "ham"]),
+        };
+        let mut expected = vec![1];
+        expected.extend_from_slice(&7_500u16.to_le_bytes());
+        assert_eq!(coerce(&output_type, "ham", Some(0.75)), Ok(expected));
+
+        let mut expected = vec![0];
+        expected.extend_from_slice(&UNKNOWN_CONFIDENCE_BPS.to_le_bytes());
+        assert_eq!(coerce(&output_type, "spam", None), Ok(expected));
+    }
+
+    #[test]
+    fn find_label_matches_whole_words() {
+        let yes_no = labels(&["yes", "no"]);
+        assert_eq!(find_label(&yes_no, "No."), Ok(1));
+        assert_eq!(find_label(&yes_no, "The answer is yes"), Ok(0));
+        assert!(find_label(&yes_no, "I don't know").is_err());
+        assert!(find_label(&yes_no, "yes or no").is_err());
+
+        let sentiments = labels(&["very positive", "positive"]);
+        assert_eq!(find_label(&sentiments, "Very positive!"), Ok(0));
+        assert_eq!(find_label(&sentiments, "It reads positive overall"), Ok(1));
+    }
+
+    #[test]
+    fn quantize_cuts_and_scales() {
+        let embedding = EmbeddingConfig {
+            quantization: Quantization::I8,
+            max_dimensions: 3,
+        };
+        let encoded = quantize(&embedding, &[0.5, -1.0, 0.25, 0.9]).unwrap();
+        assert_eq!(encoded, [3, 0, 0, 0, 64, -127i8 as u8, 32]);
+        assert!(quantize(&embedding, &[]).is_err());
+
+        let embedding = EmbeddingConfig {
+            quantization: Quantization::F16,
+            max_dimensions: 8,
+        };
+        let encoded = quantize(&embedding, &[1.0]).unwrap();
+        assert_eq!(encoded, [1, 0, 0, 0, 0x00, 0x3c]);
+    }
+}
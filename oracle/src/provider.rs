@@ -0,0 +1,236 @@
+use crate::types::{
+    ApiResponse, BatchOperation, BatchRequestBody, Content, EmbedRequestBody, EmbedResponse,
+    GenerationConfig, Part, RequestBody,
+};
+use reqwest::Client;
+use serde_json::Value;
+use serde_json::json;
+use std::{error::Error, future::Future, pin::Pin};
+
+pub type ProviderFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, Box<dyn Error>>> + 'a>>;
+
+#[derive(Clone, Copy)]
+pub struct LlmRequest<'a> {
+    pub prompt: &'a str,
+    /// JSON schema the answer has to follow, through the provider's structured output support.
+    pub response_schema: Option<&'a Value>,
+    /// Labels the answer is restricted to, for classifications.
+    pub labels: Option<&'a [String]>,
+}
+
+pub struct Generation {
+    pub text: String,
+    /// Probability the model gave its answer, when the provider reports logprobs.
+    pub probability: Option<f64>,
+}
+
+/// A model API the oracle can run inferences on.
+pub trait LlmProvider {
+    /// Model the oracle attests responses for.
+    fn model_id(&self) -> &str;
+
+    /// Model used for embedding requests.
+    fn embedding_model_id(&self) -> &str;
+
+    fn generate<'a>(&'a self, request: LlmRequest<'a>) -> ProviderFuture<'a, Generation>;
+
+    /// Embeds `text` in at most `dimensions` values.
+    fn embed<'a>(&'a self, text: &'a str, dimensions: u16) -> ProviderFuture<'a, Vec<f32>>;
+
+    /// Hands the prompts to the provider's batch API and returns the job to poll, `None`
+    /// when there is no batch API and the prompts have to be answered one by one.
+    fn submit_batch<'a>(&'a self, _prompts: &'a [String]) -> ProviderFuture<'a, Option<String>> {
+        Box::pin(async { Ok(None) })
+    }
+
+    /// The responses of a submitted batch job in prompt order, `None` while it's still running.
+    fn poll_batch<'a>(&'a self, _job: &'a str) -> ProviderFuture<'a, Option<Vec<String>>> {
+        Box::pin(async { Err("no batch api".into()) })
+    }
+}
+
+pub struct Gemini {
+    client: Client,
+    api_key: String,
+    model_id: String,
+    embedding_model_id: String,
+}
+
+impl Gemini {
+    pub fn new(client: Client, api_key: &str, model_id: &str, embedding_model_id: &str) -> Self {
+        Self {
+            client,
+            api_key: api_key.to_string(),
+            model_id: model_id.to_string(),
+            embedding_model_id: embedding_model_id.to_string(),
+        }
+    }
+}
+
+impl LlmProvider for Gemini {
+    fn model_id(&self) -> &str {
+        &self.model_id
+    }
+
+    fn embedding_model_id(&self) -> &str {
+        &self.embedding_model_id
+    }
+
+    fn generate<'a>(&'a self, request: LlmRequest<'a>) -> ProviderFuture<'a, Generation> {
+        Box::pin(async move {
+            let generation_config = match (request.labels, request.response_schema) {
+                // enum mode keeps the answer to one of the labels, logprobs tell how sure it is
+                (Some(labels), _) => Some(GenerationConfig {
+                    response_mime_type: "text/x.enum".to_string(),
+                    response_schema: Some(json!({ "type": "STRING", "enum": labels })),
+                    response_json_schema: None,
+                    response_logprobs: Some(true),
+                }),
+                (None, Some(schema)) => Some(GenerationConfig {
+                    response_mime_type: "application/json".to_string(),
+                    response_schema: None,
+                    response_json_schema: Some(schema.clone()),
+                    response_logprobs: None,
+                }),
+                (None, None) => None,
+            };
+            let request_body = RequestBody {
+                contents: vec![Content {
+                    parts: vec![Part {
+                        text: request.prompt.to_string(),
+                    }],
+                }],
+                generation_config,
+            };
+
+            let url = format!(
+                "https://generativelanguage.googleapis.com/v1beta/models/{}:generateContent",
+                self.model_id
+            );
+
+            let response = self
+                .client
+                .post(url)
+                .header("Content-Type", "application/json")
+                .header("x-goog-api-key", &self.api_key)
+                .json(&request_body)
+                .send()
+                .await;
+
+            match response {
+                Ok(res) => {
+                    let api_res: ApiResponse = res.json().await?;
+                    let candidate = &api_res.candidates[0];
+                    let text = candidate.content.parts[0].text.clone();
+                    log::info!("ai res: {}", text);
+                    // the answer's probability is the product of its tokens' probabilities
+                    let probability = candidate.logprobs_result.as_ref().map(|logprobs| {
+                        logprobs
+                            .chosen_candidates
+                            .iter()
+                            .map(|token| token.log_probability)
+                            .sum::<f64>()
+                            .exp()
+                    });
+                    Ok(Generation { text, probability })
+                }
+                Err(e) => {
+                    log::error!("Failed to get ai response: {}", e);
+                    Err(Box::new(e) as Box<dyn Error>)
+                }
+            }
+        })
+    }
+
+    fn embed<'a>(&'a self, text: &'a str, dimensions: u16) -> ProviderFuture<'a, Vec<f32>> {
+        Box::pin(async move {
+            let request_body = EmbedRequestBody {
+                content: Content {
+                    parts: vec![Part {
+                        text: text.to_string(),
+                    }],
+                },
+                output_dimensionality: dimensions,
+            };
+
+            let url = format!(
+                "https://generativelanguage.googleapis.com/v1beta/models/{}:embedContent",
+                self.embedding_model_id
+            );
+
+            let res = self
+                .client
+                .post(url)
+                .header("Content-Type", "application/json")
+                .header("x-goog-api-key", &self.api_key)
+                .json(&request_body)
+                .send()
+                .await?;
+            let embed_res: EmbedResponse = res.json().await?;
+            log::info!(
+                "embedding of {} dimensions",
+                embed_res.embedding.values.len()
+            );
+            Ok(embed_res.embedding.values)
+        })
+    }
+
+    fn submit_batch<'a>(&'a self, prompts: &'a [String]) -> ProviderFuture<'a, Option<String>> {
+        Box::pin(async move {
+            let request_body = BatchRequestBody::new(prompts);
+            let url = format!(
+                "https://generativelanguage.googleapis.com/v1beta/models/{}:batchGenerateContent",
+                self.model_id
+            );
+
+            let res = self
+                .client
+                .post(url)
+                .header("Content-Type", "application/json")
+                .header("x-goog-api-key", &self.api_key)
+                .json(&request_body)
+                .send()
+                .await?;
+            let operation: BatchOperation = res.error_for_status()?.json().await?;
+            log::info!(
+                "Submitted batch job {} of {} prompts",
+                operation.name,
+                prompts.len()
+            );
+            Ok(Some(operation.name))
+        })
+    }
+
+    fn poll_batch<'a>(&'a self, job: &'a str) -> ProviderFuture<'a, Option<Vec<String>>> {
+        Box::pin(async move {
+            let url = format!("https://generativelanguage.googleapis.com/v1beta/{}", job);
+
+            let res = self
+                .client
+                .get(url)
+                .header("x-goog-api-key", &self.api_key)
+                .send()
+                .await?;
+            let operation: BatchOperation = res.error_for_status()?.json().await?;
+            if !operation.done {
+                return Ok(None);
+            }
+
+            let mut responses = operation
+                .response
+                .ok_or_else(|| format!("batch job {} finished without responses", job))?
+                .inlined_responses
+                .inlined_responses;
+            // responses carry the prompt index as their key, their order isn't guaranteed
+            responses.sort_by_key(|item| item.key());
+            responses
+                .into_iter()
+                .map(|item| match item.response {
+                    Some(response) => Ok(response.candidates[0].content.parts[0].text.clone()),
+                    None => Err(format!("batch job {} has a failed prompt", job).into()),
+                })
+                .collect::<Result<Vec<_>, Box<dyn Error>>>()
+                .map(Some)
+        })
+    }
+}
@@ -0,0 +1,259 @@
+use crate::callback::{
+    ComputeBudget, oracle_node_address, send_transaction, send_transaction_with_budget,
+    submissions_address,
+};
+use crate::lookup_tables::LookupTableManager;
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas, system_program};
+use solana_client::rpc_client::RpcClient;
+use solana_llm_oracle::{Inference, ORACLE_IDENTITY, Submissions};
+use solana_sdk::{
+    hash::hash,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+};
+use std::{collections::HashMap, error::Error};
+
+struct PendingReveal {
+    nonce: u64,
+    response: Vec<u8>,
+    salt: [u8; 32],
+}
+
+/// Responses this oracle committed to and still has to reveal once the commit window closes.
+/// They only live in memory, a restart in between forfeits the reveal.
+#[derive(Default)]
+pub struct PendingReveals {
+    pending: HashMap<Pubkey, PendingReveal>,
+}
+
+impl PendingReveals {
+    /// Sends `Submissions::commitment` of the response and keeps the response around for the reveal.
+    pub fn commit(
+        &mut self,
+        rpc_client: &RpcClient,
+        payer: &Keypair,
+        program_id: &Pubkey,
+        inference_pubkey: &Pubkey,
+        inference: &Inference,
+        response: Vec<u8>,
+    ) -> Result<(), Box<dyn Error>> {
+        let salt = salt(payer, inference_pubkey, inference.nonce);
+        let commit_instruction = Instruction {
+            program_id: *program_id,
+            accounts: solana_llm_oracle::accounts::CommitResponse {
+                oracle: payer.pubkey(),
+                oracle_node: oracle_node_address(program_id, &payer.pubkey()),
+                inference: *inference_pubkey,
+                submissions: submissions_address(program_id, inference_pubkey),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: solana_llm_oracle::instruction::CommitResponse {
+                commitment: Submissions::commitment(
+                    &payer.pubkey(),
+                    inference_pubkey,
+                    inference.nonce,
+                    &response,
+                    &salt,
+                ),
+            }
+            .data(),
+        };
+        send_transaction(rpc_client, payer, &[commit_instruction], &[])?;
+
+        self.pending.insert(
+            *inference_pubkey,
+            PendingReveal {
+                nonce: inference.nonce,
+                response,
+                salt,
+            },
+        );
+        Ok(())
+    }
+
+    /// Reveals every pending response whose reveal window is open, and drops the ones that
+    /// can't be revealed anymore.
+    pub fn reveal_due(
+        &mut self,
+        rpc_client: &RpcClient,
+        payer: &Keypair,
+        config_pda: &Pubkey,
+        program_id: &Pubkey,
+        lookup_tables: &mut LookupTableManager,
+    ) -> Result<(), Box<dyn Error>> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        let slot = rpc_client.get_slot()?;
+
+        let mut done = Vec::new();
+        for (inference_pubkey, pending) in &self.pending {
+            let inference = rpc_client
+                .get_account(inference_pubkey)
+                .ok()
+                .and_then(|account| Inference::try_deserialize(&mut account.data.as_slice()).ok());
+            let submissions = rpc_client
+                .get_account(&submissions_address(program_id, inference_pubkey))
+                .ok()
+                .and_then(|account| {
+                    Submissions::try_deserialize(&mut account.data.as_slice()).ok()
+                });
+
+            let (Some(inference), Some(submissions)) = (inference, submissions) else {
+                done.push(*inference_pubkey);
+                continue;
+            };
+            if inference.is_processed || inference.nonce != pending.nonce {
+                done.push(*inference_pubkey);
+                continue;
+            }
+            if slot <= submissions.commit_deadline {
+                continue;
+            }
+
+            if slot > submissions.reveal_deadline {
+                log::warn!("Missed the reveal window of {:?}", inference_pubkey);
+                // nobody can reveal anymore, the main oracle closes the request out on what was revealed
+                if payer.pubkey() == ORACLE_IDENTITY {
+                    expire(
+                        rpc_client,
+                        payer,
+                        config_pda,
+                        program_id,
+                        inference_pubkey,
+                        &inference,
+                        pending,
+                        lookup_tables,
+                    )?;
+                }
+            } else if let Err(e) = reveal(
+                rpc_client,
+                payer,
+                config_pda,
+                program_id,
+                inference_pubkey,
+                &inference,
+                pending,
+                lookup_tables,
+            ) {
+                log::error!("Failed to reveal {:?}: {:?}", inference_pubkey, e);
+            }
+            done.push(*inference_pubkey);
+        }
+
+        for inference_pubkey in done {
+            self.pending.remove(&inference_pubkey);
+        }
+        Ok(())
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn reveal(
+    rpc_client: &RpcClient,
+    payer: &Keypair,
+    config_pda: &Pubkey,
+    program_id: &Pubkey,
+    inference_pubkey: &Pubkey,
+    inference: &Inference,
+    pending: &PendingReveal,
+    lookup_tables: &mut LookupTableManager,
+) -> Result<(), Box<dyn Error>> {
+    let lookup_table_accounts = lookup_tables.fetch_tables(rpc_client, &inference.lookup_tables);
+
+    let mut reveal_instruction = Instruction {
+        program_id: *program_id,
+        accounts: solana_llm_oracle::accounts::RevealResponse {
+            oracle: payer.pubkey(),
+            oracle_node: oracle_node_address(program_id, &payer.pubkey()),
+            config: *config_pda,
+            inference: *inference_pubkey,
+            submissions: submissions_address(program_id, inference_pubkey),
+            program: inference.callback_program_id,
+        }
+        .to_account_metas(None),
+        data: solana_llm_oracle::instruction::RevealResponse {
+            response: pending.response.clone(),
+            salt: pending.salt,
+        }
+        .data(),
+    };
+    reveal_instruction
+        .accounts
+        .extend(callback_account_metas(inference));
+
+    // the reveal that completes the quorum fires the callback
+    send_transaction_with_budget(
+        rpc_client,
+        payer,
+        &[reveal_instruction],
+        &lookup_table_accounts,
+        &ComputeBudget::for_callback(inference),
+    )?;
+    Ok(())
+}
+
+// settles on the revealed set if it still reaches the threshold, fails the request otherwise
+#[allow(clippy::too_many_arguments)]
+fn expire(
+    rpc_client: &RpcClient,
+    payer: &Keypair,
+    config_pda: &Pubkey,
+    program_id: &Pubkey,
+    inference_pubkey: &Pubkey,
+    inference: &Inference,
+    pending: &PendingReveal,
+    lookup_tables: &mut LookupTableManager,
+) -> Result<(), Box<dyn Error>> {
+    log::warn!("Expiring the quorum of {:?}", inference_pubkey);
+    let lookup_table_accounts = lookup_tables.fetch_tables(rpc_client, &inference.lookup_tables);
+
+    let mut expire_instruction = Instruction {
+        program_id: *program_id,
+        accounts: solana_llm_oracle::accounts::ExpireQuorum {
+            caller: payer.pubkey(),
+            config: *config_pda,
+            inference: *inference_pubkey,
+            submissions: submissions_address(program_id, inference_pubkey),
+            user: inference.user,
+            program: inference.callback_program_id,
+        }
+        .to_account_metas(None),
+        data: solana_llm_oracle::instruction::ExpireQuorum {
+            response: Some(pending.response.clone()),
+        }
+        .data(),
+    };
+    expire_instruction
+        .accounts
+        .extend(callback_account_metas(inference));
+
+    send_transaction_with_budget(
+        rpc_client,
+        payer,
+        &[expire_instruction],
+        &lookup_table_accounts,
+        &ComputeBudget::for_callback(inference),
+    )?;
+    Ok(())
+}
+
+fn callback_account_metas(inference: &Inference) -> impl Iterator<Item = AccountMeta> + '_ {
+    inference
+        .callback_account_metas
+        .iter()
+        .map(|meta| AccountMeta {
+            pubkey: meta.pubkey,
+            is_signer: false,
+            is_writable: meta.is_writable,
+        })
+}
+
+// ed25519 signatures are deterministic, so the salt can be rebuilt but never guessed by other oracles
+fn salt(payer: &Keypair, inference_pubkey: &Pubkey, nonce: u64) -> [u8; 32] {
+    let message = [inference_pubkey.as_ref(), &nonce.to_le_bytes()].concat();
+    hash(payer.sign_message(&message).as_ref()).to_bytes()
+}
@@ -0,0 +1,76 @@
+use crate::callback::send_transaction;
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use solana_client::{rpc_client::RpcClient, rpc_config::RpcProgramAccountsConfig};
+use solana_llm_oracle::{ORACLE_IDENTITY, Schedule};
+use solana_sdk::{
+    account::from_account, clock::Clock, instruction::Instruction, pubkey::Pubkey,
+    signature::Keypair, signer::Signer, sysvar,
+};
+use std::error::Error;
+
+/// Fires every schedule that is due and can still pay for a run. The inference a run
+/// refreshes comes back through the inference subscription and is answered from there.
+pub fn run_due_schedules(
+    rpc_client: &RpcClient,
+    payer: &Keypair,
+    config_pda: &Pubkey,
+    program_id: &Pubkey,
+    program_config: &RpcProgramAccountsConfig,
+) -> Result<(), Box<dyn Error>> {
+    if payer.pubkey() != ORACLE_IDENTITY
+        || crate::load_config_account(rpc_client, config_pda)
+            .map_or(true, |config| config.requests_paused)
+    {
+        return Ok(());
+    }
+
+    let clock: Clock = from_account(&rpc_client.get_account(&sysvar::clock::ID)?)
+        .ok_or("failed to decode the clock sysvar")?;
+    let schedules =
+        rpc_client.get_program_accounts_with_config(program_id, program_config.clone())?;
+
+    for (schedule_pubkey, account) in schedules {
+        let Ok(schedule) = Schedule::try_deserialize(&mut account.data.as_slice()) else {
+            continue;
+        };
+        if !schedule.is_funded() || !schedule.is_due(&clock) {
+            continue;
+        }
+
+        let inference = Schedule::inference_address(&schedule_pubkey, &schedule.chat_context);
+        // still answering the previous run
+        let pending = rpc_client
+            .get_account(&inference)
+            .ok()
+            .and_then(|account| {
+                solana_llm_oracle::Inference::try_deserialize(&mut account.data.as_slice()).ok()
+            })
+            .is_none_or(|inference| !inference.is_processed);
+        if pending {
+            continue;
+        }
+
+        log::info!(
+            "Running schedule {:?}, run {}/{}",
+            schedule_pubkey,
+            schedule.runs + 1,
+            schedule.max_runs
+        );
+        let run_instruction = Instruction {
+            program_id: *program_id,
+            accounts: solana_llm_oracle::accounts::RunSchedule {
+                payer: payer.pubkey(),
+                schedule: schedule_pubkey,
+                chat_context: schedule.chat_context,
+                inference,
+                config: *config_pda,
+            }
+            .to_account_metas(None),
+            data: solana_llm_oracle::instruction::RunSchedule {}.data(),
+        };
+        if let Err(e) = send_transaction(rpc_client, payer, &[run_instruction], &[]) {
+            log::error!("Failed to run schedule {:?}: {:?}", schedule_pubkey, e);
+        }
+    }
+    Ok(())
+}
@@ -13,13 +13,156 @@ pub struct Content {
 #[derive(Serialize)]
 pub struct RequestBody {
     pub contents: Vec<Content>,
+    #[serde(rename = "generationConfig", skip_serializing_if = "Option::is_none")]
+    pub generation_config: Option<GenerationConfig>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerationConfig {
+    pub response_mime_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_schema: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_json_schema: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_logprobs: Option<bool>,
 }
 #[derive(Deserialize, Debug)]
 pub struct Candidate {
     pub content: Content,
+    #[serde(rename = "logprobsResult")]
+    pub logprobs_result: Option<LogprobsResult>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LogprobsResult {
+    pub chosen_candidates: Vec<TokenLogprob>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenLogprob {
+    pub log_probability: f64,
 }
 
 #[derive(Deserialize, Debug)]
 pub struct ApiResponse {
     pub candidates: Vec<Candidate>,
 }
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbedRequestBody {
+    pub content: Content,
+    pub output_dimensionality: u16,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Embedding {
+    pub values: Vec<f32>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct EmbedResponse {
+    pub embedding: Embedding,
+}
+
+#[derive(Serialize)]
+pub struct BatchRequestBody {
+    pub batch: BatchConfig,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchConfig {
+    pub display_name: String,
+    pub input_config: BatchInputConfig,
+}
+
+#[derive(Serialize)]
+pub struct BatchInputConfig {
+    pub requests: BatchRequests,
+}
+
+#[derive(Serialize)]
+pub struct BatchRequests {
+    pub requests: Vec<BatchRequest>,
+}
+
+#[derive(Serialize)]
+pub struct BatchRequest {
+    pub request: RequestBody,
+    pub metadata: BatchItemMetadata,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BatchItemMetadata {
+    pub key: String,
+}
+
+impl BatchRequestBody {
+    pub fn new(prompts: &[String]) -> Self {
+        let requests = prompts
+            .iter()
+            .enumerate()
+            .map(|(index, prompt)| BatchRequest {
+                request: RequestBody {
+                    contents: vec![Content {
+                        parts: vec![Part {
+                            text: prompt.clone(),
+                        }],
+                    }],
+                    generation_config: None,
+                },
+                metadata: BatchItemMetadata {
+                    key: index.to_string(),
+                },
+            })
+            .collect();
+        Self {
+            batch: BatchConfig {
+                display_name: "solana-llm-oracle".to_string(),
+                input_config: BatchInputConfig {
+                    requests: BatchRequests { requests },
+                },
+            },
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct BatchOperation {
+    pub name: String,
+    #[serde(default)]
+    pub done: bool,
+    pub response: Option<BatchOutput>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchOutput {
+    pub inlined_responses: InlinedResponses,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct InlinedResponses {
+    pub inlined_responses: Vec<InlinedResponse>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct InlinedResponse {
+    pub response: Option<ApiResponse>,
+    pub metadata: Option<BatchItemMetadata>,
+}
+
+impl InlinedResponse {
+    pub fn key(&self) -> usize {
+        self.metadata
+            .as_ref()
+            .and_then(|metadata| metadata.key.parse().ok())
+            .unwrap_or(usize::MAX)
+    }
+}
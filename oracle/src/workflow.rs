@@ -0,0 +1,72 @@
+use crate::callback::record_workflow_step;
+use crate::provider::{LlmProvider, LlmRequest};
+use anchor_lang::AccountDeserialize;
+use solana_client::rpc_client::RpcClient;
+use solana_llm_oracle::Workflow;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair};
+use std::error::Error;
+
+/// Runs every step of the inference's workflow but the last, recording each response,
+/// and returns the last step's prompt for the regular completion. `Err` holds the
+/// reason to fail the inference with.
+#[allow(clippy::too_many_arguments)]
+pub async fn run_workflow(
+    provider: &dyn LlmProvider,
+    rpc_client: &RpcClient,
+    payer: &Keypair,
+    program_id: &Pubkey,
+    inference_pubkey: &Pubkey,
+    workflow_pubkey: &Pubkey,
+    chat_text: &str,
+    input: &str,
+) -> Result<Result<String, String>, Box<dyn Error>> {
+    let workflow = match rpc_client
+        .get_account(workflow_pubkey)
+        .ok()
+        .filter(|account| account.owner == *program_id)
+        .and_then(|account| Workflow::try_deserialize(&mut account.data.as_slice()).ok())
+    {
+        Some(workflow) => workflow,
+        None => return Ok(Err(format!("workflow {} not found", workflow_pubkey))),
+    };
+    let Some((last, steps)) = workflow.steps.split_last() else {
+        return Ok(Err("workflow has no steps".to_string()));
+    };
+
+    let mut previous = String::new();
+    for (step, template) in steps.iter().enumerate() {
+        let prompt = format!("{}, {}", chat_text, render(template, input, &previous));
+        let request = LlmRequest {
+            prompt: &prompt,
+            response_schema: None,
+            labels: None,
+        };
+        previous = crate::with_retries(|| provider.generate(request))
+            .await?
+            .text;
+
+        log::info!(
+            "Workflow step {}/{} of inference {:?} done",
+            step + 1,
+            workflow.steps.len(),
+            inference_pubkey
+        );
+        record_workflow_step(
+            rpc_client,
+            payer,
+            program_id,
+            inference_pubkey,
+            workflow_pubkey,
+            step as u8,
+            &previous,
+        )?;
+    }
+
+    Ok(Ok(render(last, input, &previous)))
+}
+
+fn render(template: &str, input: &str, previous: &str) -> String {
+    template
+        .replace("{input}", input)
+        .replace("{previous}", previous)
+}
@@ -1,10 +1,12 @@
 use anchor_lang::prelude::*;
 
 use solana_llm_oracle::cpi::{
-    accounts::{CreateChat, CreateLlmInference},
-    create_chat, create_llm_inference,
+    accounts::{CreateChat, CreateLlmInference, SetChatVisibility},
+    create_chat, create_llm_inference, set_chat_visibility,
+};
+use solana_llm_oracle::{
+    state::AccountMeta, ChatContext, ChatVisibility, Config, InferenceOptions, OutputType,
 };
-use solana_llm_oracle::{state::AccountMeta, ChatContext, Config};
 
 declare_id!("3PXKKoDvK8TUF7mmszeXozkaZS7KGbtUEg3dn3r8PTkL");
 
@@ -18,17 +20,34 @@ pub mod defi_score_agent_example {
         ctx.accounts.agent.chat_context = ctx.accounts.chat_context.key();
         ctx.accounts.agent.bump = ctx.bumps.agent;
 
-        // Create the context for the AI agent
+        // Create the context for the AI agent, owned by the agent pda so only this program controls it
         let cpi_program = ctx.accounts.oracle_program.to_account_info();
         let cpi_accounts = CreateChat {
-            user: ctx.accounts.signer.to_account_info(),
+            user: ctx.accounts.agent.to_account_info(),
+            payer: ctx.accounts.signer.to_account_info(),
             chat_context: ctx.accounts.chat_context.to_account_info(),
+            config: ctx.accounts.config.to_account_info(),
             system_program: ctx.accounts.system_program.to_account_info(),
         };
 
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        let signer_seeds: &[&[&[u8]]] = &[&[b"agent", &[ctx.bumps.agent]]];
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
 
         create_chat(cpi_ctx, AGENT_DESC.to_string(), seed)?;
+
+        // every user scores themselves against the one agent
+        let cpi_accounts = SetChatVisibility {
+            user: ctx.accounts.agent.to_account_info(),
+            payer: ctx.accounts.signer.to_account_info(),
+            chat_context: ctx.accounts.chat_context.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.oracle_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        set_chat_visibility(cpi_ctx, ChatVisibility::Public)?;
         Ok(())
     }
 
@@ -37,11 +56,16 @@ pub mod defi_score_agent_example {
 
         let cpi_accounts = CreateLlmInference {
             user: ctx.accounts.user.to_account_info(),
+            payer: ctx.accounts.user.to_account_info(),
             inference: ctx.accounts.inference.to_account_info(),
             chat_context: ctx.accounts.chat_context.to_account_info(),
+            usage: ctx.accounts.usage.to_account_info(),
+            config: ctx.accounts.config.to_account_info(),
             system_program: ctx.accounts.system_program.to_account_info(),
+            requester: None,
         };
 
+        // the agent's chat is public, each user gets an inference of their own
         let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
 
         let disc: [u8; 8] = instruction::CallbackFromLlm::DISCRIMINATOR
@@ -65,30 +89,23 @@ pub mod defi_score_agent_example {
                     is_writable: true,
                 },
             ]),
+            // the oracle hands the score over as a u8, no parsing on-chain
+            Some(InferenceOptions {
+                output_type: Some(OutputType::U8 { min: 0, max: 100 }),
+                ..Default::default()
+            }),
         )?;
         Ok(())
     }
-    pub fn callback_from_llm(ctx: Context<CallbackFromLlm>, response: String) -> Result<()> {
+    pub fn callback_from_llm(ctx: Context<CallbackFromLlm>, score: u8) -> Result<()> {
         // Ensure the identity is a signer
         if !ctx.accounts.config.to_account_info().is_signer {
             return Err(ProgramError::InvalidAccountData.into());
         }
 
-        msg!("AI response received: {}", response);
-
-        // Parse the response as u8
-        let parsed_score: u8 = response.trim().parse::<u8>().map_err(|_| {
-            msg!("Failed to parse AI response as a number");
-            ProgramError::InvalidInstructionData
-        })?;
-
-        if parsed_score > 100 {
-            msg!("Score exceeds 100, clamping to 100");
-        }
-
-        // Update the cred_score account
+        // The oracle program only lets scores within 0..=100 through
         let cred_score_account = &mut ctx.accounts.cred_score;
-        cred_score_account.score = parsed_score.min(100);
+        cred_score_account.score = score;
 
         msg!("Stored score: {}", cred_score_account.score);
 
@@ -114,6 +131,9 @@ pub struct Initialize<'info> {
     #[account(mut)]
     pub chat_context: AccountInfo<'info>,
 
+    /// CHECK: checked in the oracle program
+    pub config: AccountInfo<'info>,
+
     /// CHECK: the oracle program id
     #[account(address = solana_llm_oracle::ID)]
     pub oracle_program: AccountInfo<'info>,
@@ -137,6 +157,13 @@ pub struct ChatWithLlm<'info> {
     #[account(mut)]
     pub inference: AccountInfo<'info>,
 
+    /// CHECK: the user's rate limit usage, checked in oracle program
+    #[account(mut)]
+    pub usage: AccountInfo<'info>,
+
+    /// CHECK: Checked in oracle program
+    pub config: AccountInfo<'info>,
+
     #[account(seeds = [b"agent"], bump = agent.bump)]
     pub agent: Account<'info, Agent>,
 
@@ -1,4 +1,81 @@
 use anchor_lang::prelude::*;
 
+pub const ADMIN_IDENTITY: Pubkey = pubkey!("grvFMybwWoinrAp39feYxkq3JJQ7NY5oC3X9rNH26x7");
+
 #[constant]
 pub const ORACLE_IDENTITY: Pubkey = pubkey!("oRcjdxYJn7k8ujkMuh3phZGLnuRZ4VF3s3TZt5NEttE");
+
+/// Upper bound on requester supplied address lookup tables per inference.
+pub const MAX_LOOKUP_TABLES: usize = 4;
+
+/// Largest response the oracle can deliver, bounded by the CPI instruction data limit.
+pub const MAX_RESPONSE_LEN: u32 = 10_000;
+
+/// Longest model id the oracle can attest to, matches `Attestation::model_id`.
+pub const MAX_MODEL_ID_LEN: usize = 32;
+
+/// Most oracles a single quorum inference can ask.
+pub const MAX_QUORUM_SIZE: usize = 16;
+
+/// Lamports an oracle has to keep staked to take part in quorum inferences.
+pub const MIN_ORACLE_STAKE: u64 = 1_000_000_000;
+
+/// Most lamports one contradicting attestation can cost an oracle, the whole minimum stake.
+pub const MAX_ATTESTATION_SLASH: u64 = MIN_ORACLE_STAKE;
+
+/// Most lamports one missed reveal can cost an oracle.
+pub const MAX_MISSED_REVEAL_SLASH: u64 = MIN_ORACLE_STAKE / 10;
+
+/// Slots unstaked lamports stay slashable before they can be withdrawn, about two days.
+pub const UNBONDING_SLOTS: u64 = 432_000;
+
+/// Lamports a challenger locks to dispute a proposed response, lost if the challenge is rejected.
+pub const CHALLENGE_BOND: u64 = 100_000_000;
+
+/// Matching votes of staked oracles that resolve a challenge without the admin.
+pub const CHALLENGE_VOTES: usize = 3;
+
+/// Longest reason a challenger can give.
+pub const MAX_CHALLENGE_REASON_LEN: usize = 200;
+
+/// Most labels an `OutputType::Label` can choose from.
+pub const MAX_OUTPUT_LABELS: usize = 32;
+
+/// Longest single label of an `OutputType::Label`.
+pub const MAX_OUTPUT_LABEL_LEN: usize = 32;
+
+/// Confidence of a classification at full certainty, in basis points.
+pub const MAX_CONFIDENCE_BPS: u16 = 10_000;
+
+/// Most dimensions an embedding inference can ask for, f16 vectors at the cap still fit `MAX_RESPONSE_LEN`.
+pub const MAX_EMBEDDING_DIMENSIONS: u16 = 4096;
+
+/// Seed of the pda a program signs `create_llm_inference` with to be recorded as the requester.
+pub const REQUESTER_SEED: &[u8] = b"requester";
+
+/// Compute units of a callback when the requester only caps the priority fee.
+pub const DEFAULT_CALLBACK_COMPUTE_UNITS: u32 = 300_000;
+
+/// Most compute units a transaction can ask for.
+pub const MAX_CALLBACK_COMPUTE_UNITS: u32 = 1_400_000;
+
+/// Compute unit price the oracle pays when the requester only sets a limit, in micro-lamports.
+pub const DEFAULT_PRIORITY_FEE: u64 = 200_000;
+
+/// Base fee of a callback transaction, 5000 lamports each for the oracle's signature and the ed25519 attestation.
+pub const CALLBACK_SIGNATURE_FEE: u64 = 10_000;
+
+/// Most steps a workflow can chain.
+pub const MAX_WORKFLOW_STEPS: usize = 8;
+
+/// Most prompts a single batch can carry.
+pub const MAX_BATCH_SIZE: usize = 64;
+
+/// Response bytes a batch callback carries inline, split between the items of an aggregated one.
+pub const MAX_BATCH_RESPONSE_BYTES: usize = 700;
+
+/// Lamports a schedule pays the oracle per run, covers the run and callback transactions at the default compute budget.
+pub const SCHEDULE_RUN_FEE: u64 = 150_000;
+
+/// Most entries each of a chat context's allowlists can hold.
+pub const MAX_ALLOWLIST_LEN: usize = 32;
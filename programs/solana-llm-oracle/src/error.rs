@@ -4,4 +4,138 @@ use anchor_lang::prelude::*;
 pub enum OracleError {
     #[msg("You're not an admin ser!")]
     InvalidAdmin,
+    #[msg("Too many lookup tables for a single inference")]
+    TooManyLookupTables,
+    #[msg("Response is larger than the requester allows")]
+    ResponseTooLarge,
+    #[msg("Response chunk is out of the buffer bounds")]
+    ResponseChunkOutOfBounds,
+    #[msg("Buffered response doesn't match its hash")]
+    ResponseHashMismatch,
+    #[msg("Inference is already processed")]
+    InferenceProcessed,
+    #[msg("Chat context is still being uploaded")]
+    ChatContextNotReady,
+    #[msg("Inference is already finalized")]
+    InferenceFinalized,
+    #[msg("Inference is still being uploaded")]
+    InferenceNotReady,
+    #[msg("Callback is missing a valid oracle attestation")]
+    InvalidAttestation,
+    #[msg("Model id is too long")]
+    ModelIdTooLong,
+    #[msg("Quorum threshold must be a majority of at most MAX_QUORUM_SIZE oracles")]
+    InvalidQuorum,
+    #[msg("Inference doesn't use a quorum")]
+    QuorumNotEnabled,
+    #[msg("Quorum inferences are fulfilled through submit_response")]
+    QuorumRequired,
+    #[msg("Oracle already submitted a response for this request")]
+    AlreadySubmitted,
+    #[msg("Every oracle of the quorum already submitted")]
+    QuorumFull,
+    #[msg("Inference uses commit-reveal, commit and reveal the response instead")]
+    CommitRevealRequired,
+    #[msg("Inference doesn't use commit-reveal")]
+    CommitRevealNotEnabled,
+    #[msg("Commit window is closed")]
+    CommitWindowClosed,
+    #[msg("Reveal window isn't open")]
+    RevealWindowNotOpen,
+    #[msg("Oracle has no commitment to reveal")]
+    NotCommitted,
+    #[msg("Revealed response doesn't match the commitment")]
+    CommitmentMismatch,
+    #[msg("Oracle doesn't have enough stake")]
+    InsufficientStake,
+    #[msg("Oracle still has stake to withdraw")]
+    StakeNotWithdrawn,
+    #[msg("Stake is still unbonding")]
+    StakeUnbonding,
+    #[msg("Offense can't be proven with the given accounts")]
+    InvalidOffense,
+    #[msg("Quorum inferences can't use a dispute window")]
+    InvalidDisputeWindow,
+    #[msg("Inference has a dispute window, propose the response instead")]
+    DisputeWindowRequired,
+    #[msg("Inference doesn't have a dispute window")]
+    DisputeWindowNotEnabled,
+    #[msg("Response is already proposed")]
+    ResponseProposed,
+    #[msg("Response isn't proposed")]
+    ResponseNotProposed,
+    #[msg("Response buffer belongs to an earlier request")]
+    StaleResponse,
+    #[msg("Dispute window is closed")]
+    DisputeWindowClosed,
+    #[msg("Dispute window is still open")]
+    DisputeWindowOpen,
+    #[msg("Response is challenged")]
+    ResponseChallenged,
+    #[msg("Challenge reason is too long")]
+    ChallengeReasonTooLong,
+    #[msg("Challenge can still be resolved")]
+    ChallengePending,
+    #[msg("Only the admin or a staked oracle can resolve challenges")]
+    InvalidResolver,
+    #[msg("Output type has an empty range or too many labels")]
+    InvalidOutputType,
+    #[msg("Response isn't a valid value of the requested output type")]
+    InvalidTypedOutput,
+    #[msg("An inference can have an output type or a response schema, not both")]
+    ConflictingOutputFormats,
+    #[msg("Embedding dimensions are out of bounds or don't fit the response limit")]
+    InvalidEmbedding,
+    #[msg("Embedding doesn't match the requested dimensions")]
+    InvalidEmbeddingOutput,
+    #[msg("Requester isn't the requester pda of the claimed program")]
+    InvalidRequester,
+    #[msg("Inference wasn't requested by this program")]
+    RequesterMismatch,
+    #[msg("Callback compute unit limit is out of bounds")]
+    InvalidComputeBudget,
+    #[msg("Workflow needs between 1 and MAX_WORKFLOW_STEPS steps")]
+    InvalidWorkflow,
+    #[msg("Workflow steps have to be recorded in order")]
+    WorkflowStepOutOfOrder,
+    #[msg("Inference doesn't run this workflow")]
+    WorkflowMismatch,
+    #[msg("Batch needs between 1 and MAX_BATCH_SIZE prompts")]
+    InvalidBatchSize,
+    #[msg("Batch item is out of bounds or already fulfilled")]
+    InvalidBatchItem,
+    #[msg("Batch uses a different callback mode")]
+    BatchCallbackMismatch,
+    #[msg("Schedule needs a non-zero interval and run count")]
+    InvalidSchedule,
+    #[msg("Schedule isn't due yet or its previous run is still pending")]
+    ScheduleNotDue,
+    #[msg("Schedule has used up its runs or its balance")]
+    ScheduleExhausted,
+    #[msg("Chat context isn't open to this user")]
+    ChatNotPermitted,
+    #[msg("Allowlist holds more than MAX_ALLOWLIST_LEN entries")]
+    AllowlistTooLong,
+    #[msg("Chat context doesn't allow callbacks into this program")]
+    CallbackProgramNotAllowed,
+    #[msg("Chat context doesn't accept inferences from this requester")]
+    RequesterNotAllowed,
+    #[msg("Rate limit needs a non-zero request count and window")]
+    InvalidRateLimit,
+    #[msg("Too many requests in the current rate limit window")]
+    RateLimited,
+    #[msg("New requests are paused")]
+    RequestsPaused,
+    #[msg("Callbacks are paused")]
+    CallbacksPaused,
+    #[msg("Quorum inferences can't run a workflow")]
+    QuorumWorkflow,
+    #[msg("Batch answers have to fit in one callback, see MAX_BATCH_RESPONSE_BYTES")]
+    InvalidBatchResponseLen,
+    #[msg("Oracle was already slashed for this submission")]
+    AlreadySlashed,
+    #[msg("Slash amount exceeds the cap for this offense")]
+    InvalidSlashAmount,
+    #[msg("Reveal window of this quorum is still open")]
+    RevealWindowOpen,
 }
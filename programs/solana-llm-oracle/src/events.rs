@@ -0,0 +1,123 @@
+use anchor_lang::prelude::*;
+
+#[event]
+#[derive(Debug)]
+pub struct ChatCreated {
+    pub user: Pubkey,
+    pub chat_context: Pubkey,
+    pub slot: u64,
+}
+
+/// Emitted once an inference is ready for the oracle.
+#[event]
+#[derive(Debug)]
+pub struct InferenceRequested {
+    pub user: Pubkey,
+    pub chat_context: Pubkey,
+    pub inference: Pubkey,
+    pub nonce: u64,
+    pub callback_program: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct InferenceFulfilled {
+    pub user: Pubkey,
+    pub chat_context: Pubkey,
+    pub inference: Pubkey,
+    pub nonce: u64,
+    pub callback_program: Pubkey,
+    /// sha256 of the response the callback received.
+    pub response_hash: [u8; 32],
+    pub slot: u64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct InferenceFailed {
+    pub user: Pubkey,
+    pub chat_context: Pubkey,
+    pub inference: Pubkey,
+    pub nonce: u64,
+    pub callback_program: Pubkey,
+    pub reason: String,
+    pub slot: u64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct InferenceCancelled {
+    pub user: Pubkey,
+    pub chat_context: Pubkey,
+    pub inference: Pubkey,
+    pub nonce: u64,
+    pub callback_program: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct WorkflowStepRecorded {
+    pub inference: Pubkey,
+    pub nonce: u64,
+    pub workflow: Pubkey,
+    pub step: u8,
+    /// The intermediate response, cut short when it doesn't fit in a transaction.
+    pub response: String,
+    /// Hash of the full response, the one kept on-chain.
+    pub response_hash: [u8; 32],
+    pub slot: u64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct BatchRequested {
+    pub user: Pubkey,
+    pub chat_context: Pubkey,
+    pub batch: Pubkey,
+    pub size: u16,
+    pub callback_program: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct BatchFulfilled {
+    pub user: Pubkey,
+    pub chat_context: Pubkey,
+    pub batch: Pubkey,
+    pub callback_program: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct BatchFailed {
+    pub user: Pubkey,
+    pub chat_context: Pubkey,
+    pub batch: Pubkey,
+    pub callback_program: Pubkey,
+    pub reason: String,
+    pub slot: u64,
+}
+
+/// Emitted when the oracle fires a scheduled inference, next to its `InferenceRequested`.
+#[event]
+#[derive(Debug)]
+pub struct ScheduleRan {
+    pub user: Pubkey,
+    pub schedule: Pubkey,
+    pub inference: Pubkey,
+    pub run: u32,
+    pub fee: u64,
+    pub slot: u64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct PauseUpdated {
+    pub requests_paused: bool,
+    pub callbacks_paused: bool,
+    pub slot: u64,
+}
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::OracleError, ChatContext, Config};
+
+#[derive(Accounts)]
+#[instruction(text: String)]
+pub struct AppendChatText<'info> {
+    pub user: Signer<'info>,
+    /// Pays for the longer text, so a pda owner doesn't have to.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"chat_context", user.key().as_ref(), chat_context.seed.to_le_bytes().as_ref()],
+        bump = chat_context.bump,
+        realloc = chat_context.to_account_info().data_len() + text.len(),
+        realloc::payer = payer,
+        realloc::zero = false
+    )]
+    pub chat_context: Account<'info, ChatContext>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.requests_paused @ OracleError::RequestsPaused
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+impl AppendChatText<'_> {
+    // appending takes the chat out of service until `finalize_chat`, so no inference runs on half a prompt
+    pub fn append_chat_text(&mut self, text: String) -> Result<()> {
+        self.chat_context.text.push_str(&text);
+        self.chat_context.is_ready = false;
+        Ok(())
+    }
+}
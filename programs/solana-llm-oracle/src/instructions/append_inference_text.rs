@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::OracleError, ChatContext, Config, Inference};
+
+#[derive(Accounts)]
+#[instruction(text: String)]
+pub struct AppendInferenceText<'info> {
+    pub user: Signer<'info>,
+    /// Pays for the longer prompt, so a pda requester doesn't have to.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub chat_context: Account<'info, ChatContext>,
+    #[account(
+        mut,
+        seeds = [Inference::seed(), user.key().as_ref(), chat_context.key().as_ref()],
+        bump,
+        constraint = !inference.is_ready @ OracleError::InferenceFinalized,
+        realloc = inference.to_account_info().data_len() + text.len(),
+        realloc::payer = payer,
+        realloc::zero = false
+    )]
+    pub inference: Account<'info, Inference>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.requests_paused @ OracleError::RequestsPaused
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+impl AppendInferenceText<'_> {
+    pub fn append_inference_text(&mut self, text: String) -> Result<()> {
+        self.inference.text.push_str(&text);
+        Ok(())
+    }
+}
@@ -0,0 +1,137 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{instruction::Instruction, program::invoke_signed},
+};
+
+use crate::{
+    error::OracleError, events::BatchFulfilled, Batch, BatchCallback, Config, ORACLE_IDENTITY,
+};
+
+#[derive(Accounts)]
+pub struct CallbackBatch<'info> {
+    #[account(mut, address = ORACLE_IDENTITY)]
+    pub payer: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        constraint = !batch.is_processed @ OracleError::InferenceProcessed
+    )]
+    pub batch: Account<'info, Batch>,
+    /// CHECK: the callback program; this ixn is just a proxy
+    #[account(address = batch.callback_program_id)]
+    pub program: AccountInfo<'info>,
+}
+
+impl<'info> CallbackBatch<'info> {
+    pub fn callback_batch_item(
+        &mut self,
+        index: u16,
+        response: String,
+        remaining_accounts: Vec<AccountInfo<'info>>,
+    ) -> Result<()> {
+        require!(
+            self.batch.callback_mode == BatchCallback::PerItem,
+            OracleError::BatchCallbackMismatch
+        );
+        require!(
+            response.len() <= self.batch.max_response_len as usize,
+            OracleError::ResponseTooLarge
+        );
+        let fulfilled = self
+            .batch
+            .fulfilled
+            .get_mut(index as usize)
+            .filter(|fulfilled| !**fulfilled)
+            .ok_or(OracleError::InvalidBatchItem)?;
+        *fulfilled = true;
+
+        let data = (index, response).try_to_vec()?;
+        self.invoke_batch_callback(data, remaining_accounts)
+    }
+
+    pub fn callback_batch(
+        &mut self,
+        responses: Vec<String>,
+        remaining_accounts: Vec<AccountInfo<'info>>,
+    ) -> Result<()> {
+        require!(
+            self.batch.callback_mode == BatchCallback::Aggregated,
+            OracleError::BatchCallbackMismatch
+        );
+        require!(
+            responses.len() == self.batch.prompts.len(),
+            OracleError::InvalidBatchItem
+        );
+        require!(
+            responses
+                .iter()
+                .all(|response| response.len() <= self.batch.max_response_len as usize),
+            OracleError::ResponseTooLarge
+        );
+        self.batch.fulfilled.fill(true);
+
+        let data = responses.try_to_vec()?;
+        self.invoke_batch_callback(data, remaining_accounts)
+    }
+
+    fn invoke_batch_callback(
+        &mut self,
+        data: Vec<u8>,
+        remaining_accounts: Vec<AccountInfo<'info>>,
+    ) -> Result<()> {
+        require!(!self.config.callbacks_paused, OracleError::CallbacksPaused);
+        if remaining_accounts
+            .iter()
+            .any(|acc| acc.key().eq(&self.config.key()))
+        {
+            return Err(ProgramError::InvalidAccountData.into());
+        }
+
+        let mut account_metas = vec![AccountMeta {
+            pubkey: self.config.key(),
+            is_signer: true,
+            is_writable: false,
+        }];
+        account_metas.extend(
+            self.batch
+                .callback_account_metas
+                .iter()
+                .map(|meta| AccountMeta {
+                    pubkey: meta.pubkey,
+                    is_signer: false,
+                    is_writable: meta.is_writable,
+                }),
+        );
+
+        if self.batch.fulfilled.iter().all(|fulfilled| *fulfilled) {
+            self.batch.is_processed = true;
+            emit!(BatchFulfilled {
+                user: self.batch.user,
+                chat_context: self.batch.chat_context,
+                batch: self.batch.key(),
+                callback_program: self.program.key(),
+                slot: Clock::get()?.slot,
+            });
+        }
+
+        let instruction = Instruction {
+            program_id: self.program.key(),
+            accounts: account_metas,
+            data: [self.batch.callback_discriminator.as_slice(), &data].concat(),
+        };
+
+        let mut account_infos = vec![self.config.to_account_info()];
+        account_infos.extend(remaining_accounts);
+
+        invoke_signed(
+            &instruction,
+            &account_infos,
+            &[&[b"config", &[self.config.bump]]],
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{hash::hash, sysvar},
+};
+
+use crate::{
+    error::OracleError, invoke_callback, verify_attestation, Config, Inference, ResponseBuffer,
+    ORACLE_IDENTITY,
+};
+
+#[derive(Accounts)]
+pub struct CallbackFromBuffer<'info> {
+    #[account(mut, address = ORACLE_IDENTITY)]
+    pub payer: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        constraint = inference.quorum.is_none() @ OracleError::QuorumRequired,
+        constraint = inference.dispute_slots == 0 @ OracleError::DisputeWindowRequired
+    )]
+    pub inference: Account<'info, Inference>,
+    #[account(
+        mut,
+        close = payer,
+        seeds = [ResponseBuffer::seed(), inference.key().as_ref()],
+        bump = response_buffer.bump,
+        constraint = response_buffer.nonce == inference.nonce @ OracleError::StaleResponse
+    )]
+    pub response_buffer: Account<'info, ResponseBuffer>,
+    /// CHECK: the callback program; this ixn is just a proxy
+    pub program: AccountInfo<'info>,
+    /// CHECK: instructions sysvar, the oracle's ed25519 attestation is read from it
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+}
+
+impl<'info> CallbackFromBuffer<'info> {
+    pub fn callback_from_buffer(
+        &mut self,
+        model_id: String,
+        remaining_accounts: Vec<AccountInfo<'info>>,
+    ) -> Result<()> {
+        let response = std::mem::take(&mut self.response_buffer.data);
+        let response_hash = hash(&response).to_bytes();
+        require!(
+            response_hash == self.response_buffer.response_hash,
+            OracleError::ResponseHashMismatch
+        );
+
+        let attestation =
+            verify_attestation(&self.instructions, &self.inference, response_hash, model_id)?;
+        self.inference.attestation = Some(attestation);
+
+        invoke_callback(
+            &self.config,
+            &mut self.inference,
+            &self.program,
+            &response,
+            remaining_accounts,
+            &self.payer.to_account_info(),
+        )
+    }
+}
@@ -1,9 +1,21 @@
 use anchor_lang::{
     prelude::*,
-    solana_program::{instruction::Instruction, program::invoke_signed},
+    solana_program::{
+        ed25519_program,
+        hash::hash,
+        instruction::Instruction,
+        program::invoke_signed,
+        sysvar::{self, instructions::get_instruction_relative},
+    },
 };
 
-use crate::{Config, Inference, ORACLE_IDENTITY};
+use crate::{
+    error::OracleError, events::InferenceFulfilled, Attestation, CallbackFormat, CallbackMetadata,
+    Config, Inference, MAX_MODEL_ID_LEN, ORACLE_IDENTITY,
+};
+
+// num signatures + padding + one set of signature offsets
+const ED25519_OFFSETS_END: usize = 2 + 14;
 
 #[derive(Accounts)]
 pub struct CallbackFromLlm<'info> {
@@ -15,54 +27,240 @@ pub struct CallbackFromLlm<'info> {
     )]
     pub config: Account<'info, Config>,
     /// CHECK: we accept any inference // can't be the user signing so no seed validations
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = inference.quorum.is_none() @ OracleError::QuorumRequired,
+        constraint = inference.dispute_slots == 0 @ OracleError::DisputeWindowRequired
+    )]
     pub inference: Account<'info, Inference>,
     /// CHECK: the callback program; this ixn is just a proxy
     pub program: AccountInfo<'info>,
+    /// CHECK: instructions sysvar, the oracle's ed25519 attestation is read from it
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
 }
 
 impl<'info> CallbackFromLlm<'info> {
     pub fn callback_from_llm(
         &mut self,
-        response: String,
+        response: Vec<u8>,
+        model_id: String,
         remaining_accounts: Vec<AccountInfo<'info>>,
     ) -> Result<()> {
-        let response_data = [
-            self.inference.callback_discriminator.to_vec(),
-            response.try_to_vec()?, // to_vec clones the value into a new Vec<>, is there any CU effective alternative?
-        ]
-        .concat();
-
-        let mut account_metas = vec![AccountMeta {
-            pubkey: self.config.key(),
-            is_signer: true,
-            is_writable: false,
-        }];
-
-        account_metas.extend(self.inference.callback_account_metas.iter().map(|meta| {
-            AccountMeta {
-                pubkey: meta.pubkey,
-                is_signer: false, // saves a lot of issues as false always
-                is_writable: meta.is_writable,
-            }
-        }));
-
-        self.inference.is_processed = true;
-
-        let instruction = Instruction {
-            program_id: self.program.key(),
-            accounts: account_metas,
-            data: response_data,
-        };
-
-        let mut account_infos = vec![self.config.to_account_info()];
-        account_infos = [account_infos, remaining_accounts].concat();
-
-        invoke_signed(
-            &instruction,
-            &account_infos,
-            &[&[b"config", &[self.config.bump]]],
+        require!(
+            response.len() <= self.inference.max_response_len as usize,
+            OracleError::ResponseTooLarge
+        );
+
+        let attestation = verify_attestation(
+            &self.instructions,
+            &self.inference,
+            hash(&response).to_bytes(),
+            model_id,
         )?;
-        Ok(())
+        self.inference.attestation = Some(attestation);
+
+        invoke_callback(
+            &self.config,
+            &mut self.inference,
+            &self.program,
+            &response,
+            remaining_accounts,
+            &self.payer.to_account_info(),
+        )
+    }
+}
+
+/// Checks that the instruction right before this one is the ed25519 program verifying
+/// the oracle's signature over `Attestation::message`, and returns the attestation to store.
+pub fn verify_attestation(
+    instructions: &AccountInfo,
+    inference: &Account<Inference>,
+    response_hash: [u8; 32],
+    model_id: String,
+) -> Result<Attestation> {
+    require!(
+        model_id.len() <= MAX_MODEL_ID_LEN,
+        OracleError::ModelIdTooLong
+    );
+
+    let expected_message =
+        Attestation::message(&inference.key(), inference.nonce, &response_hash, &model_id);
+    let signature = verify_ed25519(instructions, &ORACLE_IDENTITY, &expected_message)?;
+
+    Ok(Attestation {
+        response_hash,
+        model_id,
+        signature,
+    })
+}
+
+/// Checks that the instruction right before this one is the ed25519 program verifying
+/// `signer`'s signature over `message`, and returns the signature.
+pub fn verify_ed25519(
+    instructions: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+) -> Result<[u8; 64]> {
+    let ed25519_instruction = get_instruction_relative(-1, instructions)
+        .map_err(|_| error!(OracleError::InvalidAttestation))?;
+    require_keys_eq!(
+        ed25519_instruction.program_id,
+        ed25519_program::ID,
+        OracleError::InvalidAttestation
+    );
+
+    let data = &ed25519_instruction.data;
+    require!(
+        data.len() >= ED25519_OFFSETS_END && data[0] == 1,
+        OracleError::InvalidAttestation
+    );
+
+    let offset = |index: usize| u16::from_le_bytes([data[2 + index * 2], data[3 + index * 2]]);
+    // u16::MAX means the data lives in the ed25519 ixn itself, anything else could point at bytes we never see here
+    require!(
+        offset(1) == u16::MAX && offset(3) == u16::MAX && offset(6) == u16::MAX,
+        OracleError::InvalidAttestation
+    );
+
+    let slice = |start: u16, len: usize| {
+        data.get(start as usize..start as usize + len)
+            .ok_or(error!(OracleError::InvalidAttestation))
+    };
+    let signature = slice(offset(0), 64)?;
+    let pubkey = slice(offset(2), 32)?;
+    let signed_message = slice(offset(4), offset(5) as usize)?;
+
+    require!(
+        pubkey == signer.as_ref() && signed_message == message,
+        OracleError::InvalidAttestation
+    );
+
+    signature
+        .try_into()
+        .map_err(|_| error!(OracleError::InvalidAttestation))
+}
+
+/// Marks the inference processed and CPIs into the callback program with the
+/// response as a borsh `String`, or as the borsh encoded value when the inference
+/// has an output type or asks for an embedding, `config` signing as the oracle identity.
+/// `CallbackFormat::WithMetadata` adds the inference account and a `CallbackMetadata` prefix.
+/// The escrowed callback fee is paid out to `fee_recipient`.
+pub fn invoke_callback<'info>(
+    config: &Account<'info, Config>,
+    inference: &mut Account<'info, Inference>,
+    program: &AccountInfo<'info>,
+    response: &[u8],
+    remaining_accounts: Vec<AccountInfo<'info>>,
+    fee_recipient: &AccountInfo<'info>,
+) -> Result<()> {
+    require!(inference.is_ready, OracleError::InferenceNotReady);
+    require!(!config.callbacks_paused, OracleError::CallbacksPaused);
+
+    // Check if config is not in remaining accounts, oracle also sends callback_account_metas from client which are remaining accounts
+    if remaining_accounts
+        .iter()
+        .any(|acc| acc.key().eq(&config.key()))
+    {
+        return Err(ProgramError::InvalidAccountData.into());
+    }
+
+    let response_hash = hash(response).to_bytes();
+    let payload = match (&inference.output_type, &inference.embedding) {
+        (Some(output_type), _) => {
+            require!(
+                output_type.validate(response),
+                OracleError::InvalidTypedOutput
+            );
+            response.to_vec()
+        }
+        (None, Some(embedding)) => {
+            require!(
+                embedding.validate(response),
+                OracleError::InvalidEmbeddingOutput
+            );
+            response.to_vec()
+        }
+        (None, None) => response.try_to_vec()?, // same layout as a borsh String; to_vec clones the value into a new Vec<>, is there any CU effective alternative?
+    };
+
+    let mut account_metas = vec![AccountMeta {
+        pubkey: config.key(),
+        is_signer: true,
+        is_writable: false,
+    }];
+    let mut account_infos = vec![config.to_account_info()];
+
+    let response_data = match inference.callback_format {
+        CallbackFormat::Plain => [inference.callback_discriminator.as_slice(), &payload].concat(),
+        CallbackFormat::WithMetadata => {
+            account_metas.push(AccountMeta {
+                pubkey: inference.key(),
+                is_signer: false,
+                is_writable: false,
+            });
+            account_infos.push(inference.to_account_info());
+
+            let metadata = CallbackMetadata {
+                nonce: inference.nonce,
+                response_hash,
+                model_id: inference
+                    .attestation
+                    .as_ref()
+                    .map_or(String::new(), |attestation| attestation.model_id.clone()),
+            };
+            [
+                inference.callback_discriminator.as_slice(),
+                &metadata.try_to_vec()?,
+                &payload,
+            ]
+            .concat()
+        }
+    };
+
+    account_metas.extend(inference.callback_account_metas.iter().map(|meta| {
+        AccountMeta {
+            pubkey: meta.pubkey,
+            is_signer: false, // saves a lot of issues as false always
+            is_writable: meta.is_writable,
+        }
+    }));
+
+    inference.is_processed = true;
+    emit!(InferenceFulfilled {
+        user: inference.user,
+        chat_context: inference.chat_context,
+        inference: inference.key(),
+        nonce: inference.nonce,
+        callback_program: program.key(),
+        response_hash,
+        slot: Clock::get()?.slot,
+    });
+
+    // the callback reads the inference as it is after this call, not as it was loaded
+    if inference.callback_format == CallbackFormat::WithMetadata {
+        inference.exit(&crate::ID)?;
+    }
+
+    let instruction = Instruction {
+        program_id: program.key(),
+        accounts: account_metas,
+        data: response_data,
+    };
+
+    account_infos.extend(remaining_accounts);
+
+    invoke_signed(
+        &instruction,
+        &account_infos,
+        &[&[b"config", &[config.bump]]],
+    )?;
+
+    // the fee escrowed for the callback goes to whoever sent it, after the cpi so balances line up
+    let fee = std::mem::take(&mut inference.escrowed_fee);
+    if fee > 0 {
+        inference.sub_lamports(fee)?;
+        fee_recipient.add_lamports(fee)?;
     }
+    Ok(())
 }
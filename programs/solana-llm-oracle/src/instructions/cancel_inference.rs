@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::OracleError, events::InferenceCancelled, ChatContext, Inference};
+
+#[derive(Accounts)]
+pub struct CancelInference<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub chat_context: Account<'info, ChatContext>,
+    #[account(
+        mut,
+        seeds = [Inference::seed(), user.key().as_ref(), chat_context.key().as_ref()],
+        bump,
+        constraint = !inference.is_processed @ OracleError::InferenceProcessed
+    )]
+    pub inference: Account<'info, Inference>,
+}
+
+impl CancelInference<'_> {
+    // withdraws a request the oracle hasn't answered yet, it's skipped from then on
+    pub fn cancel_inference(&mut self) -> Result<()> {
+        self.inference.is_processed = true;
+
+        // nothing will call back, the callback fee goes back to the requester
+        let fee = std::mem::take(&mut self.inference.escrowed_fee);
+        if fee > 0 {
+            self.inference.sub_lamports(fee)?;
+            self.user.add_lamports(fee)?;
+        }
+
+        emit!(InferenceCancelled {
+            user: self.user.key(),
+            chat_context: self.chat_context.key(),
+            inference: self.inference.key(),
+            nonce: self.inference.nonce,
+            callback_program: self.inference.callback_program_id,
+            slot: Clock::get()?.slot,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+
+use crate::{
+    error::OracleError, Challenge, Inference, ResponseBuffer, CHALLENGE_BOND,
+    MAX_CHALLENGE_REASON_LEN,
+};
+
+#[derive(Accounts)]
+pub struct ChallengeResponse<'info> {
+    #[account(mut)]
+    pub challenger: Signer<'info>,
+    pub inference: Account<'info, Inference>,
+    #[account(
+        mut,
+        seeds = [ResponseBuffer::seed(), inference.key().as_ref()],
+        bump = response_buffer.bump,
+        constraint = response_buffer.nonce == inference.nonce @ OracleError::StaleResponse,
+        constraint = response_buffer.dispute_deadline > 0 @ OracleError::ResponseNotProposed,
+        constraint = !response_buffer.is_challenged @ OracleError::ResponseChallenged
+    )]
+    pub response_buffer: Account<'info, ResponseBuffer>,
+    #[account(
+        init,
+        payer = challenger,
+        space = 8 + Challenge::INIT_SPACE,
+        seeds = [Challenge::seed(), inference.key().as_ref(), inference.nonce.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub challenge: Account<'info, Challenge>,
+    pub system_program: Program<'info, System>,
+}
+
+impl ChallengeResponse<'_> {
+    // anyone can challenge, the bond keeps it from being free to stall callbacks
+    pub fn challenge_response(
+        &mut self,
+        reason: String,
+        bumps: &ChallengeResponseBumps,
+    ) -> Result<()> {
+        require!(
+            reason.len() <= MAX_CHALLENGE_REASON_LEN,
+            OracleError::ChallengeReasonTooLong
+        );
+        require!(
+            Clock::get()?.slot <= self.response_buffer.dispute_deadline,
+            OracleError::DisputeWindowClosed
+        );
+
+        let cpi_context = CpiContext::new(
+            self.system_program.to_account_info(),
+            Transfer {
+                from: self.challenger.to_account_info(),
+                to: self.challenge.to_account_info(),
+            },
+        );
+        transfer(cpi_context, CHALLENGE_BOND)?;
+
+        self.challenge.set_inner(Challenge {
+            inference: self.inference.key(),
+            nonce: self.inference.nonce,
+            challenger: self.challenger.key(),
+            reason,
+            upheld_by: Vec::new(),
+            rejected_by: Vec::new(),
+            bump: bumps.challenge,
+        });
+        self.response_buffer.is_challenged = true;
+        Ok(())
+    }
+}
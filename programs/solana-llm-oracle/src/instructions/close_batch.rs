@@ -0,0 +1,18 @@
+use anchor_lang::prelude::*;
+
+use crate::Batch;
+
+// gives the rent back once the batch is done, closing it early drops the pending prompts
+#[derive(Accounts)]
+pub struct CloseBatch<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        close = user,
+        has_one = user,
+        seeds = [Batch::seed(), user.key().as_ref(), batch.chat_context.as_ref(), batch.batch_id.to_le_bytes().as_ref()],
+        bump = batch.bump
+    )]
+    pub batch: Account<'info, Batch>,
+}
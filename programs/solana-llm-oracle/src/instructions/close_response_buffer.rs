@@ -0,0 +1,22 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::OracleError, Inference, ResponseBuffer, ORACLE_IDENTITY};
+
+// lets the oracle drop a half written buffer left behind by a failed delivery,
+// proposed responses only once the request they answer has been replaced
+#[derive(Accounts)]
+pub struct CloseResponseBuffer<'info> {
+    #[account(mut, address = ORACLE_IDENTITY)]
+    pub payer: Signer<'info>,
+    #[account(address = response_buffer.inference)]
+    pub inference: Account<'info, Inference>,
+    #[account(
+        mut,
+        close = payer,
+        seeds = [ResponseBuffer::seed(), response_buffer.inference.as_ref()],
+        bump = response_buffer.bump,
+        constraint = response_buffer.dispute_deadline == 0
+            || response_buffer.nonce != inference.nonce @ OracleError::ResponseProposed
+    )]
+    pub response_buffer: Account<'info, ResponseBuffer>,
+}
@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+
+use crate::{Inference, Schedule};
+
+// stops the schedule, the rent and whatever is left of the balance go back to the user
+#[derive(Accounts)]
+pub struct CloseSchedule<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        close = user,
+        has_one = user,
+        seeds = [Schedule::seed(), user.key().as_ref(), schedule.chat_context.as_ref(), schedule.schedule_id.to_le_bytes().as_ref()],
+        bump = schedule.bump
+    )]
+    pub schedule: Account<'info, Schedule>,
+    #[account(
+        mut,
+        close = user,
+        seeds = [Inference::seed(), schedule.key().as_ref(), schedule.chat_context.as_ref()],
+        bump
+    )]
+    pub inference: Account<'info, Inference>,
+}
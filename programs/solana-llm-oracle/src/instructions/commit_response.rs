@@ -0,0 +1,74 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::OracleError, reset_submissions, Inference, OracleNode, Submission, Submissions,
+    MIN_ORACLE_STAKE,
+};
+
+#[derive(Accounts)]
+pub struct CommitResponse<'info> {
+    #[account(mut)]
+    pub oracle: Signer<'info>,
+    #[account(
+        seeds = [OracleNode::seed(), oracle.key().as_ref()],
+        bump = oracle_node.bump,
+        constraint = oracle_node.stake >= MIN_ORACLE_STAKE @ OracleError::InsufficientStake
+    )]
+    pub oracle_node: Account<'info, OracleNode>,
+    #[account(
+        constraint = !inference.is_processed @ OracleError::InferenceProcessed
+    )]
+    pub inference: Account<'info, Inference>,
+    #[account(
+        init_if_needed,
+        payer = oracle,
+        space = 8 + Submissions::INIT_SPACE,
+        seeds = [Submissions::seed(), inference.key().as_ref()],
+        bump
+    )]
+    pub submissions: Account<'info, Submissions>,
+    pub system_program: Program<'info, System>,
+}
+
+impl CommitResponse<'_> {
+    pub fn commit_response(
+        &mut self,
+        commitment: [u8; 32],
+        bumps: &CommitResponseBumps,
+    ) -> Result<()> {
+        let quorum = self
+            .inference
+            .quorum
+            .as_ref()
+            .ok_or(OracleError::QuorumNotEnabled)?;
+        require!(
+            quorum.commit_reveal.is_some(),
+            OracleError::CommitRevealNotEnabled
+        );
+        let size = quorum.size as usize;
+
+        reset_submissions(&mut self.submissions, &self.inference, bumps.submissions);
+        require!(
+            Clock::get()?.slot <= self.submissions.commit_deadline,
+            OracleError::CommitWindowClosed
+        );
+
+        let oracle = self.oracle.key();
+        let entries = &mut self.submissions.entries;
+        require!(
+            !entries.iter().any(|entry| entry.oracle == oracle),
+            OracleError::AlreadySubmitted
+        );
+        require!(entries.len() < size, OracleError::QuorumFull);
+
+        entries.push(Submission {
+            oracle,
+            commitment,
+            revealed: false,
+            response_hash: [0; 32],
+            value: None,
+            slashed: false,
+        });
+        Ok(())
+    }
+}
@@ -1,20 +1,36 @@
 use anchor_lang::prelude::*;
 
-use crate::ChatContext;
+use crate::{
+    error::OracleError, events::ChatCreated, AccessPolicy, ChatContext, ChatVisibility, Config,
+};
 
 #[derive(Accounts)]
 #[instruction(text: String, seed: u8)]
 pub struct CreateChat<'info> {
-    #[account(mut)]
+    /// Owner of the chat and its seeds, a wallet or a program pda signing through `invoke_signed`.
     pub user: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
     #[account(
         init,
-        payer = user,
-        space = 8 + 4 + text.as_bytes().len() + 1 + 1,
+        payer = payer,
+        space = ChatContext::space(
+            &text,
+            None,
+            None,
+            &ChatVisibility::Private,
+            &AccessPolicy::default()
+        ),
         seeds = [b"chat_context", user.key().as_ref(), seed.to_le_bytes().as_ref()],
         bump
     )]
     pub chat_context: Account<'info, ChatContext>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.requests_paused @ OracleError::RequestsPaused
+    )]
+    pub config: Account<'info, Config>,
     pub system_program: Program<'info, System>,
 }
 
@@ -29,6 +45,18 @@ impl CreateChat<'_> {
             text,
             seed,
             bump: bumps.chat_context,
+            is_ready: true,
+            content_ref: None,
+            response_schema: None,
+            visibility: ChatVisibility::Private,
+            access_policy: AccessPolicy::default(),
+            rate_limit: None,
+        });
+
+        emit!(ChatCreated {
+            user: self.user.key(),
+            chat_context: self.chat_context.key(),
+            slot: Clock::get()?.slot,
         });
         Ok(())
     }
@@ -3,17 +3,26 @@ use anchor_lang::solana_program::system_instruction::create_account;
 use anchor_lang::system_program::{transfer, Transfer};
 use anchor_lang::{prelude::*, system_program};
 
-use crate::state::AccountMeta;
-use crate::{ChatContext, Inference};
+use crate::error::OracleError;
+use crate::events::InferenceRequested;
+use crate::state;
+use crate::{
+    CallbackBudget, ChatContext, Config, Inference, InferenceOptions, OutputType, QuorumConfig,
+    Usage, DEFAULT_CALLBACK_COMPUTE_UNITS, DEFAULT_PRIORITY_FEE, MAX_LOOKUP_TABLES,
+    MAX_RESPONSE_LEN,
+};
 
 #[derive(Accounts)]
-#[instruction(text: String, callback_program_id: Pubkey, callback_discriminator: [u8; 8], account_metas: Option<Vec<AccountMeta>>)]
+#[instruction(text: String, callback_program_id: Pubkey, callback_discriminator: [u8; 8], account_metas: Option<Vec<state::AccountMeta>>, options: Option<InferenceOptions>)]
 pub struct CreateLlmInference<'info> {
-    #[account(mut)]
+    /// Owner of the chat, a wallet or a program pda signing through `invoke_signed`.
     pub user: Signer<'info>,
+    /// Pays the inference's rent and callback fee, gets back what an earlier request left escrowed.
+    #[account(mut)]
+    pub payer: Signer<'info>,
     #[account(
-        seeds = [b"chat_context", user.key().as_ref(), chat_context.seed.to_le_bytes().as_ref()],
-        bump = chat_context.bump
+        constraint = chat_context.is_ready @ OracleError::ChatContextNotReady,
+        constraint = chat_context.is_permitted(&chat_context.key(), &user.key()) @ OracleError::ChatNotPermitted
     )]
     pub chat_context: Account<'info, ChatContext>,
     /// CHECK: the correct inference pda inside the ixn logic
@@ -23,7 +32,23 @@ pub struct CreateLlmInference<'info> {
         bump
     )]
     pub inference: AccountInfo<'info>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + Usage::INIT_SPACE,
+        seeds = [Usage::seed(), chat_context.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub usage: Account<'info, Usage>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.requests_paused @ OracleError::RequestsPaused
+    )]
+    pub config: Account<'info, Config>,
     pub system_program: Program<'info, System>,
+    /// The requesting program's `REQUESTER_SEED` pda, only needed with `requester_program`.
+    pub requester: Option<Signer<'info>>,
 }
 
 impl CreateLlmInference<'_> {
@@ -32,29 +57,151 @@ impl CreateLlmInference<'_> {
         text: String,
         callback_program_id: Pubkey,
         callback_discriminator: [u8; 8],
-        account_metas: Option<Vec<AccountMeta>>,
+        account_metas: Option<Vec<state::AccountMeta>>,
+        options: Option<InferenceOptions>,
         bumps: &CreateLlmInferenceBumps,
     ) -> Result<()> {
-        let rent = Rent::get()?;
-        let space = Inference::space(&text, account_metas.as_ref().map_or(0, |m| m.len()));
+        let options = options.unwrap_or_default();
+        require!(
+            options.lookup_tables.len() <= MAX_LOOKUP_TABLES,
+            OracleError::TooManyLookupTables
+        );
+        require!(
+            options.quorum.as_ref().is_none_or(QuorumConfig::is_valid),
+            OracleError::InvalidQuorum
+        );
+        require!(
+            options
+                .output_type
+                .as_ref()
+                .is_none_or(OutputType::is_valid),
+            OracleError::InvalidOutputType
+        );
+        // a typed value isn't json and an embedding is neither, only one can shape the answer
+        require!(
+            options.output_type.is_none() || options.response_schema.is_none(),
+            OracleError::ConflictingOutputFormats
+        );
+        require!(
+            options.embedding.is_none()
+                || (options.output_type.is_none() && options.response_schema.is_none()),
+            OracleError::ConflictingOutputFormats
+        );
+        // quorum responses settle through agreement, they never sit in a response buffer
+        require!(
+            options.quorum.is_none() || options.dispute_slots.is_none(),
+            OracleError::InvalidDisputeWindow
+        );
+        // only the main oracle can record workflow steps, the other quorum oracles couldn't run one
+        require!(
+            options.quorum.is_none() || options.workflow.is_none(),
+            OracleError::QuorumWorkflow
+        );
+        let requester_program = match (options.requester_program, &self.requester) {
+            (None, None) => None,
+            (Some(program), Some(requester))
+                if requester.key() == Inference::requester_address(&program) =>
+            {
+                Some(program)
+            }
+            _ => return err!(OracleError::InvalidRequester),
+        };
+        let access_policy = &self.chat_context.access_policy;
+        require!(
+            access_policy.allows_callback(&callback_program_id),
+            OracleError::CallbackProgramNotAllowed
+        );
+        require!(
+            access_policy.allows_requester(requester_program.as_ref()),
+            OracleError::RequesterNotAllowed
+        );
+        let callback_budget = (options.compute_unit_limit.is_some()
+            || options.priority_fee_cap.is_some())
+        .then(|| CallbackBudget {
+            compute_unit_limit: options
+                .compute_unit_limit
+                .unwrap_or(DEFAULT_CALLBACK_COMPUTE_UNITS),
+            priority_fee_cap: options.priority_fee_cap.unwrap_or(DEFAULT_PRIORITY_FEE),
+        });
+        require!(
+            callback_budget
+                .as_ref()
+                .is_none_or(CallbackBudget::is_valid),
+            OracleError::InvalidComputeBudget
+        );
+        // the chat's own limit wins over the default
+        self.usage.bump = bumps.usage;
+        if let Some(rate_limit) = self
+            .chat_context
+            .rate_limit
+            .as_ref()
+            .or(self.config.default_rate_limit.as_ref())
+        {
+            self.usage.record(Clock::get()?.slot, rate_limit)?;
+        }
+        let max_response_len = options
+            .max_response_len
+            .map_or(MAX_RESPONSE_LEN, |len| len.min(MAX_RESPONSE_LEN));
+        require!(
+            options.embedding.as_ref().is_none_or(|embedding| {
+                embedding.is_valid() && embedding.max_len() <= max_response_len as usize
+            }),
+            OracleError::InvalidEmbedding
+        );
+
         let inference_info = self.inference.to_account_info();
+        // reusing the pda keeps fields from the previous request around, fresh accounts start from default
+        let mut inference =
+            Inference::try_deserialize_unchecked(&mut inference_info.try_borrow_data()?.as_ref())
+                .unwrap_or_default();
+        // a fee still escrowed from an earlier request that never called back counts toward this one
+        let previous_fee = inference.escrowed_fee;
+
+        inference.chat_context = self.chat_context.key();
+        inference.user = self.user.key();
+        inference.nonce = inference.nonce.wrapping_add(1);
+        inference.text = text;
+        inference.content_ref = options.content_ref;
+        inference.callback_program_id = callback_program_id;
+        inference.callback_discriminator = callback_discriminator;
+        inference.callback_account_metas = account_metas.unwrap_or_default();
+        inference.callback_format = options.callback_format;
+        inference.requester_program = requester_program;
+        inference.callback_budget = callback_budget;
+        inference.escrowed_fee = callback_budget.as_ref().map_or(0, CallbackBudget::max_fee);
+        inference.lookup_tables = options.lookup_tables;
+        inference.max_response_len = max_response_len;
+        inference.output_type = options.output_type;
+        inference.response_schema = options.response_schema;
+        inference.embedding = options.embedding;
+        inference.workflow = options.workflow;
+        inference.quorum = options.quorum;
+        inference.requested_slot = Clock::get()?.slot;
+        inference.dispute_slots = options.dispute_slots.unwrap_or_default();
+        inference.is_ready = !options.chunked_upload;
+        inference.is_processed = false;
+        inference.is_failed = false;
+        inference.attestation = None;
+
+        let rent = Rent::get()?;
+        let space = inference.space();
         let current_len = inference_info.data_len();
 
         let mut additional_rent = rent.minimum_balance(space);
 
-        let user_info = self.user.to_account_info();
+        let payer_info = self.payer.to_account_info();
         let system_program_info = self.system_program.to_account_info();
 
         if inference_info.owner.eq(&system_program::ID) {
             let create_instruction = create_account(
-                &self.user.key(),
+                &self.payer.key(),
                 &self.inference.key(),
                 additional_rent,
                 space as u64,
                 &crate::ID,
             );
 
-            let account_infos = [user_info, inference_info, system_program_info];
+            let account_infos = [payer_info, inference_info, system_program_info];
 
             let user = self.user.key();
             let chat_context = self.chat_context.key();
@@ -73,7 +220,7 @@ impl CreateLlmInference<'_> {
                 let cpi_context = CpiContext::new(
                     system_program_info,
                     Transfer {
-                        from: user_info,
+                        from: payer_info,
                         to: inference_info,
                     },
                 );
@@ -82,20 +229,36 @@ impl CreateLlmInference<'_> {
             }
         }
 
-        let inference = &mut self.inference;
-        let mut inference_data = inference.try_borrow_mut_data()?;
-        let mut inference =
-            Inference::try_deserialize_unchecked(&mut inference_data.as_ref()).unwrap_or_default();
-
-        inference.chat_context = self.chat_context.key();
-        inference.user = self.user.key();
-        inference.text = text;
-        inference.callback_program_id = callback_program_id;
-        inference.callback_discriminator = callback_discriminator;
-        inference.callback_account_metas = account_metas.unwrap_or_default();
-        inference.is_processed = false;
+        if inference.escrowed_fee > previous_fee {
+            let cpi_context = CpiContext::new(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.payer.to_account_info(),
+                    to: self.inference.to_account_info(),
+                },
+            );
+            transfer(cpi_context, inference.escrowed_fee - previous_fee)?;
+        } else if previous_fee > inference.escrowed_fee {
+            self.inference
+                .sub_lamports(previous_fee - inference.escrowed_fee)?;
+            self.payer
+                .add_lamports(previous_fee - inference.escrowed_fee)?;
+        }
 
+        let mut inference_data = self.inference.try_borrow_mut_data()?;
         inference.try_serialize(&mut inference_data.as_mut())?;
+
+        // chunked uploads are announced by finalize_inference
+        if inference.is_ready {
+            emit!(InferenceRequested {
+                user: inference.user,
+                chat_context: inference.chat_context,
+                inference: self.inference.key(),
+                nonce: inference.nonce,
+                callback_program: inference.callback_program_id,
+                slot: inference.requested_slot,
+            });
+        }
         Ok(())
     }
 }
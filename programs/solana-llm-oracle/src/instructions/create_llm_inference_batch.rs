@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+
+use crate::error::OracleError;
+use crate::events::BatchRequested;
+use crate::state;
+use crate::{Batch, BatchConfig, ChatContext, Config, Usage, MAX_BATCH_SIZE};
+
+#[derive(Accounts)]
+#[instruction(batch_id: u64, prompts: Vec<String>, callback_program_id: Pubkey, callback_discriminator: [u8; 8], account_metas: Option<Vec<state::AccountMeta>>)]
+pub struct CreateLlmInferenceBatch<'info> {
+    /// Owner of the chat, a wallet or a program pda signing through `invoke_signed`.
+    pub user: Signer<'info>,
+    /// Pays the rent of the batch and the usage account.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        constraint = chat_context.is_ready @ OracleError::ChatContextNotReady,
+        constraint = chat_context.is_permitted(&chat_context.key(), &user.key()) @ OracleError::ChatNotPermitted
+    )]
+    pub chat_context: Account<'info, ChatContext>,
+    #[account(
+        init,
+        payer = payer,
+        space = Batch::space(&prompts, account_metas.as_ref().map_or(0, Vec::len)),
+        seeds = [Batch::seed(), user.key().as_ref(), chat_context.key().as_ref(), batch_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub batch: Account<'info, Batch>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + Usage::INIT_SPACE,
+        seeds = [Usage::seed(), chat_context.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub usage: Account<'info, Usage>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.requests_paused @ OracleError::RequestsPaused
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+impl CreateLlmInferenceBatch<'_> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_llm_inference_batch(
+        &mut self,
+        batch_id: u64,
+        prompts: Vec<String>,
+        callback_program_id: Pubkey,
+        callback_discriminator: [u8; 8],
+        account_metas: Option<Vec<state::AccountMeta>>,
+        config: BatchConfig,
+        bumps: &CreateLlmInferenceBatchBumps,
+    ) -> Result<()> {
+        require!(
+            !prompts.is_empty() && prompts.len() <= MAX_BATCH_SIZE,
+            OracleError::InvalidBatchSize
+        );
+        require!(
+            config.is_valid(prompts.len()),
+            OracleError::InvalidBatchResponseLen
+        );
+        let access_policy = &self.chat_context.access_policy;
+        require!(
+            access_policy.allows_callback(&callback_program_id),
+            OracleError::CallbackProgramNotAllowed
+        );
+        // there's no requester program to check, a requester list keeps these out
+        require!(
+            access_policy.allows_requester(None),
+            OracleError::RequesterNotAllowed
+        );
+
+        // every prompt counts as a request of its own
+        if let Some(rate_limit) = self
+            .chat_context
+            .rate_limit
+            .as_ref()
+            .or(self.config.default_rate_limit.as_ref())
+        {
+            let slot = Clock::get()?.slot;
+            for _ in &prompts {
+                self.usage.record(slot, rate_limit)?;
+            }
+        }
+
+        let size = prompts.len();
+        self.batch.set_inner(Batch {
+            chat_context: self.chat_context.key(),
+            user: self.user.key(),
+            batch_id,
+            prompts,
+            callback_program_id,
+            callback_discriminator,
+            callback_account_metas: account_metas.unwrap_or_default(),
+            callback_mode: config.callback_mode,
+            max_response_len: config.max_response_len,
+            fulfilled: vec![false; size],
+            is_processed: false,
+            is_failed: false,
+            bump: bumps.batch,
+        });
+
+        emit!(BatchRequested {
+            user: self.user.key(),
+            chat_context: self.chat_context.key(),
+            batch: self.batch.key(),
+            size: size as u16,
+            callback_program: callback_program_id,
+            slot: Clock::get()?.slot,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+use crate::error::OracleError;
+use crate::state;
+use crate::{ChatContext, Config, Inference, Schedule, ScheduleConfig, MAX_RESPONSE_LEN};
+
+#[derive(Accounts)]
+#[instruction(schedule_id: u64, prompt: String, callback_program_id: Pubkey, callback_discriminator: [u8; 8], account_metas: Option<Vec<state::AccountMeta>>)]
+pub struct CreateSchedule<'info> {
+    /// Owner of the chat and the schedule, a wallet or a program pda signing through `invoke_signed`.
+    pub user: Signer<'info>,
+    /// Pays the rent and funds the deposit.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        constraint = chat_context.is_ready @ OracleError::ChatContextNotReady,
+        constraint = chat_context.is_permitted(&chat_context.key(), &user.key()) @ OracleError::ChatNotPermitted
+    )]
+    pub chat_context: Account<'info, ChatContext>,
+    #[account(
+        init,
+        payer = payer,
+        space = Schedule::space(&prompt, account_metas.as_ref().map_or(0, Vec::len)),
+        seeds = [Schedule::seed(), user.key().as_ref(), chat_context.key().as_ref(), schedule_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub schedule: Account<'info, Schedule>,
+    // created up front so the oracle only ever refreshes it
+    #[account(
+        init,
+        payer = payer,
+        space = Schedule::inference_space(&prompt, account_metas.as_deref().unwrap_or_default()),
+        seeds = [Inference::seed(), schedule.key().as_ref(), chat_context.key().as_ref()],
+        bump
+    )]
+    pub inference: Account<'info, Inference>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.requests_paused @ OracleError::RequestsPaused
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+impl CreateSchedule<'_> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_schedule(
+        &mut self,
+        schedule_id: u64,
+        prompt: String,
+        callback_program_id: Pubkey,
+        callback_discriminator: [u8; 8],
+        account_metas: Option<Vec<state::AccountMeta>>,
+        config: ScheduleConfig,
+        bumps: &CreateScheduleBumps,
+    ) -> Result<()> {
+        require!(config.is_valid(), OracleError::InvalidSchedule);
+        let access_policy = &self.chat_context.access_policy;
+        require!(
+            access_policy.allows_callback(&callback_program_id),
+            OracleError::CallbackProgramNotAllowed
+        );
+        // there's no requester program to check, a requester list keeps these out
+        require!(
+            access_policy.allows_requester(None),
+            OracleError::RequesterNotAllowed
+        );
+
+        let callback_account_metas = account_metas.unwrap_or_default();
+        self.inference.set_inner(Inference {
+            chat_context: self.chat_context.key(),
+            user: self.schedule.key(),
+            text: prompt.clone(),
+            callback_program_id,
+            callback_discriminator,
+            callback_account_metas: callback_account_metas.clone(),
+            max_response_len: MAX_RESPONSE_LEN,
+            // nothing to answer until the first run
+            is_processed: true,
+            ..Default::default()
+        });
+        self.schedule.set_inner(Schedule {
+            chat_context: self.chat_context.key(),
+            user: self.user.key(),
+            schedule_id,
+            prompt,
+            interval: config.interval,
+            callback_program_id,
+            callback_discriminator,
+            callback_account_metas,
+            balance: config.deposit,
+            max_runs: config.max_runs,
+            runs: 0,
+            last_run_slot: 0,
+            last_run_timestamp: 0,
+            bump: bumps.schedule,
+        });
+
+        if config.deposit > 0 {
+            let cpi_context = CpiContext::new(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.payer.to_account_info(),
+                    to: self.schedule.to_account_info(),
+                },
+            );
+            transfer(cpi_context, config.deposit)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::OracleError, Config, Workflow, MAX_WORKFLOW_STEPS};
+
+#[derive(Accounts)]
+#[instruction(seed: u8, steps: Vec<String>)]
+pub struct CreateWorkflow<'info> {
+    /// Owner of the workflow and its seeds, a wallet or a program pda signing through `invoke_signed`.
+    pub user: Signer<'info>,
+    /// Pays the workflow's rent.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = Workflow::space(&steps),
+        seeds = [Workflow::seed(), user.key().as_ref(), seed.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub workflow: Account<'info, Workflow>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.requests_paused @ OracleError::RequestsPaused
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+impl CreateWorkflow<'_> {
+    pub fn create_workflow(
+        &mut self,
+        seed: u8,
+        steps: Vec<String>,
+        bumps: &CreateWorkflowBumps,
+    ) -> Result<()> {
+        require!(
+            !steps.is_empty() && steps.len() <= MAX_WORKFLOW_STEPS,
+            OracleError::InvalidWorkflow
+        );
+
+        self.workflow.set_inner(Workflow {
+            user: self.user.key(),
+            steps,
+            seed,
+            bump: bumps.workflow,
+        });
+        Ok(())
+    }
+}
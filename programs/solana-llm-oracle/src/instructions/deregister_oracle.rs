@@ -0,0 +1,17 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::OracleError, OracleNode, ADMIN_IDENTITY};
+
+#[derive(Accounts)]
+pub struct DeregisterOracle<'info> {
+    #[account(mut, address = ADMIN_IDENTITY @ OracleError::InvalidAdmin)]
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        close = admin,
+        seeds = [OracleNode::seed(), oracle_node.identity.as_ref()],
+        bump = oracle_node.bump,
+        constraint = oracle_node.stake == 0 && oracle_node.unbonding == 0 @ OracleError::StakeNotWithdrawn
+    )]
+    pub oracle_node: Account<'info, OracleNode>,
+}
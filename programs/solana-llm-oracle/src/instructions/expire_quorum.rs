@@ -0,0 +1,103 @@
+use anchor_lang::{prelude::*, solana_program::hash::hash};
+
+use crate::{
+    encode_numeric, error::OracleError, mark_failed, settle_quorum, AgreementMode, Config,
+    Inference, Submissions,
+};
+
+// anyone can close out a commit-reveal quorum once its reveal window is over, so oracles
+// that committed and never revealed can't hold the request open
+#[derive(Accounts)]
+pub struct ExpireQuorum<'info> {
+    #[account(mut)]
+    pub caller: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        constraint = !inference.is_processed @ OracleError::InferenceProcessed
+    )]
+    pub inference: Account<'info, Inference>,
+    #[account(
+        mut,
+        seeds = [Submissions::seed(), inference.key().as_ref()],
+        bump = submissions.bump,
+        constraint = submissions.nonce == inference.nonce @ OracleError::NotCommitted
+    )]
+    pub submissions: Account<'info, Submissions>,
+    /// CHECK: gets the escrowed callback fee back when the quorum fails
+    #[account(mut, address = inference.user)]
+    pub user: AccountInfo<'info>,
+    /// CHECK: the callback program; this ixn is just a proxy
+    #[account(address = inference.callback_program_id)]
+    pub program: AccountInfo<'info>,
+}
+
+impl<'info> ExpireQuorum<'info> {
+    /// Settles on whatever was revealed by the deadline if that still reaches the threshold,
+    /// otherwise fails the inference and refunds the callback fee. Exact mode needs the
+    /// agreed `response`, only its hash is on-chain.
+    pub fn expire_quorum(
+        &mut self,
+        response: Option<Vec<u8>>,
+        remaining_accounts: Vec<AccountInfo<'info>>,
+    ) -> Result<()> {
+        let quorum = self
+            .inference
+            .quorum
+            .clone()
+            .ok_or(OracleError::QuorumNotEnabled)?;
+        require!(
+            self.submissions.reveal_deadline > 0
+                && Clock::get()?.slot > self.submissions.reveal_deadline,
+            OracleError::RevealWindowOpen
+        );
+
+        let agreed_response = match quorum.mode {
+            AgreementMode::Exact => match self.submissions.exact_majority(quorum.threshold) {
+                Some(response_hash) => {
+                    let response = response.ok_or(OracleError::ResponseHashMismatch)?;
+                    require!(
+                        hash(&response).to_bytes() == response_hash,
+                        OracleError::ResponseHashMismatch
+                    );
+                    Some(response)
+                }
+                None => None,
+            },
+            AgreementMode::Median => self
+                .submissions
+                .median_agreement(quorum.tolerance)
+                .filter(|(_, agreed)| agreed.len() >= quorum.threshold as usize)
+                .map(|(median, _)| encode_numeric(self.inference.output_type.as_ref(), median)),
+        };
+
+        if let Some(response) = agreed_response {
+            return settle_quorum(
+                &self.config,
+                &mut self.inference,
+                &mut self.submissions,
+                &quorum,
+                &self.program,
+                response,
+                remaining_accounts,
+                &self.caller.to_account_info(),
+            );
+        }
+
+        mark_failed(
+            &mut self.inference,
+            "Quorum not reached before the reveal deadline".to_string(),
+        )?;
+        // nothing will call back, the callback fee goes back to the requester
+        let fee = std::mem::take(&mut self.inference.escrowed_fee);
+        if fee > 0 {
+            self.inference.sub_lamports(fee)?;
+            self.user.add_lamports(fee)?;
+        }
+        Ok(())
+    }
+}
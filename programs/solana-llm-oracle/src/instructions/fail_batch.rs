@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::OracleError, events::BatchFailed, Batch, ORACLE_IDENTITY};
+
+#[derive(Accounts)]
+pub struct FailBatch<'info> {
+    #[account(address = ORACLE_IDENTITY)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        constraint = !batch.is_processed @ OracleError::InferenceProcessed
+    )]
+    pub batch: Account<'info, Batch>,
+}
+
+impl FailBatch<'_> {
+    // closes out a batch whose answers can't be delivered whole, items already called back stay delivered
+    pub fn fail_batch(&mut self, reason: String) -> Result<()> {
+        msg!("Batch failed: {}", reason);
+        self.batch.is_processed = true;
+        self.batch.is_failed = true;
+
+        emit!(BatchFailed {
+            user: self.batch.user,
+            chat_context: self.batch.chat_context,
+            batch: self.batch.key(),
+            callback_program: self.batch.callback_program_id,
+            reason,
+            slot: Clock::get()?.slot,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::OracleError, events::InferenceFailed, Inference, ORACLE_IDENTITY};
+
+#[derive(Accounts)]
+pub struct FailInference<'info> {
+    #[account(address = ORACLE_IDENTITY)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        constraint = !inference.is_processed @ OracleError::InferenceProcessed
+    )]
+    pub inference: Account<'info, Inference>,
+}
+
+impl FailInference<'_> {
+    // closes out a request the oracle can't serve, e.g. off-chain content that doesn't match its hash
+    pub fn fail_inference(&mut self, reason: String) -> Result<()> {
+        mark_failed(&mut self.inference, reason)
+    }
+}
+
+/// Closes the request out as failed without a callback.
+pub fn mark_failed(inference: &mut Account<Inference>, reason: String) -> Result<()> {
+    msg!("Inference failed: {}", reason);
+    inference.is_processed = true;
+    inference.is_failed = true;
+
+    emit!(InferenceFailed {
+        user: inference.user,
+        chat_context: inference.chat_context,
+        inference: inference.key(),
+        nonce: inference.nonce,
+        callback_program: inference.callback_program_id,
+        reason,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
@@ -0,0 +1,21 @@
+use anchor_lang::prelude::*;
+
+use crate::ChatContext;
+
+#[derive(Accounts)]
+pub struct FinalizeChat<'info> {
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"chat_context", user.key().as_ref(), chat_context.seed.to_le_bytes().as_ref()],
+        bump = chat_context.bump
+    )]
+    pub chat_context: Account<'info, ChatContext>,
+}
+
+impl FinalizeChat<'_> {
+    pub fn finalize_chat(&mut self) -> Result<()> {
+        self.chat_context.is_ready = true;
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::OracleError, events::InferenceRequested, ChatContext, Config, Inference};
+
+#[derive(Accounts)]
+pub struct FinalizeInference<'info> {
+    pub user: Signer<'info>,
+    pub chat_context: Account<'info, ChatContext>,
+    #[account(
+        mut,
+        seeds = [Inference::seed(), user.key().as_ref(), chat_context.key().as_ref()],
+        bump,
+        constraint = !inference.is_ready @ OracleError::InferenceFinalized
+    )]
+    pub inference: Account<'info, Inference>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.requests_paused @ OracleError::RequestsPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+impl FinalizeInference<'_> {
+    // the oracle only picks up ready inferences
+    pub fn finalize_inference(&mut self) -> Result<()> {
+        self.inference.is_ready = true;
+        self.inference.requested_slot = Clock::get()?.slot;
+
+        emit!(InferenceRequested {
+            user: self.user.key(),
+            chat_context: self.chat_context.key(),
+            inference: self.inference.key(),
+            nonce: self.inference.nonce,
+            callback_program: self.inference.callback_program_id,
+            slot: self.inference.requested_slot,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::OracleError, invoke_callback, Config, Inference, ResponseBuffer, ORACLE_IDENTITY,
+};
+
+#[derive(Accounts)]
+pub struct FinalizeResponse<'info> {
+    #[account(mut, address = ORACLE_IDENTITY)]
+    pub payer: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        constraint = !inference.is_processed @ OracleError::InferenceProcessed
+    )]
+    pub inference: Account<'info, Inference>,
+    #[account(
+        mut,
+        close = payer,
+        seeds = [ResponseBuffer::seed(), inference.key().as_ref()],
+        bump = response_buffer.bump,
+        constraint = response_buffer.nonce == inference.nonce @ OracleError::StaleResponse,
+        constraint = response_buffer.dispute_deadline > 0 @ OracleError::ResponseNotProposed,
+        constraint = !response_buffer.is_challenged @ OracleError::ResponseChallenged
+    )]
+    pub response_buffer: Account<'info, ResponseBuffer>,
+    /// CHECK: the callback program; this ixn is just a proxy
+    #[account(address = inference.callback_program_id)]
+    pub program: AccountInfo<'info>,
+}
+
+impl<'info> FinalizeResponse<'info> {
+    // an unchallenged response goes out once its dispute window has passed
+    pub fn finalize_response(&mut self, remaining_accounts: Vec<AccountInfo<'info>>) -> Result<()> {
+        require!(
+            Clock::get()?.slot > self.response_buffer.dispute_deadline,
+            OracleError::DisputeWindowOpen
+        );
+
+        let response = std::mem::take(&mut self.response_buffer.data);
+        invoke_callback(
+            &self.config,
+            &mut self.inference,
+            &self.program,
+            &response,
+            remaining_accounts,
+            &self.payer.to_account_info(),
+        )
+    }
+}
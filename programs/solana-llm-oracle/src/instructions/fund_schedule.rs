@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+use crate::{error::OracleError, Config, Schedule};
+
+#[derive(Accounts)]
+pub struct FundSchedule<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        has_one = user,
+        seeds = [Schedule::seed(), user.key().as_ref(), schedule.chat_context.as_ref(), schedule.schedule_id.to_le_bytes().as_ref()],
+        bump = schedule.bump
+    )]
+    pub schedule: Account<'info, Schedule>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.requests_paused @ OracleError::RequestsPaused
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+impl FundSchedule<'_> {
+    // tops up the prepaid balance, and raises the run cap for schedules that should keep going
+    pub fn fund_schedule(&mut self, amount: u64, additional_runs: u32) -> Result<()> {
+        let cpi_context = CpiContext::new(
+            self.system_program.to_account_info(),
+            Transfer {
+                from: self.user.to_account_info(),
+                to: self.schedule.to_account_info(),
+            },
+        );
+        transfer(cpi_context, amount)?;
+
+        self.schedule.balance += amount;
+        self.schedule.max_runs = self.schedule.max_runs.saturating_add(additional_runs);
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::OracleError, Inference, ResponseBuffer, ORACLE_IDENTITY};
+
+#[derive(Accounts)]
+#[instruction(total_len: u32)]
+pub struct InitResponseBuffer<'info> {
+    #[account(mut, address = ORACLE_IDENTITY)]
+    pub payer: Signer<'info>,
+    pub inference: Account<'info, Inference>,
+    #[account(
+        init,
+        payer = payer,
+        space = ResponseBuffer::space(total_len),
+        seeds = [ResponseBuffer::seed(), inference.key().as_ref()],
+        bump
+    )]
+    pub response_buffer: Account<'info, ResponseBuffer>,
+    pub system_program: Program<'info, System>,
+}
+
+impl InitResponseBuffer<'_> {
+    pub fn init_response_buffer(
+        &mut self,
+        total_len: u32,
+        response_hash: [u8; 32],
+        bumps: &InitResponseBufferBumps,
+    ) -> Result<()> {
+        require!(
+            !self.inference.is_processed,
+            OracleError::InferenceProcessed
+        );
+        require!(
+            total_len <= self.inference.max_response_len,
+            OracleError::ResponseTooLarge
+        );
+
+        self.response_buffer.set_inner(ResponseBuffer {
+            inference: self.inference.key(),
+            nonce: self.inference.nonce,
+            response_hash,
+            data: vec![0; total_len as usize],
+            dispute_deadline: 0,
+            is_challenged: false,
+            bump: bumps.response_buffer,
+        });
+        Ok(())
+    }
+}
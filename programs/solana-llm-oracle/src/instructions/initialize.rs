@@ -1,10 +1,10 @@
 use anchor_lang::prelude::*;
 
-use crate::Config;
+use crate::{error::OracleError, Config, ADMIN_IDENTITY};
 
 #[derive(Accounts)]
 pub struct Initialize<'info> {
-    #[account(mut, address = pubkey!("grvFMybwWoinrAp39feYxkq3JJQ7NY5oC3X9rNH26x7"))]
+    #[account(mut, address = ADMIN_IDENTITY @ OracleError::InvalidAdmin)]
     pub admin: Signer<'info>,
     #[account(
         init,
@@ -19,7 +19,12 @@ pub struct Initialize<'info> {
 
 impl Initialize<'_> {
     pub fn initialize(&mut self, bumps: &InitializeBumps) -> Result<()> {
-        self.config.set_inner(Config { bump: bumps.config });
+        self.config.set_inner(Config {
+            bump: bumps.config,
+            default_rate_limit: None,
+            requests_paused: false,
+            callbacks_paused: false,
+        });
         Ok(())
     }
 }
@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+
+use crate::ChatContext;
+
+#[derive(Accounts)]
+#[instruction(seed: u8)]
+pub struct MigrateChat<'info> {
+    pub user: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: a legacy chat context doesn't deserialize, `ChatContext::grow` reads it by hand
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [b"chat_context", user.key().as_ref(), seed.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub chat_context: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl MigrateChat<'_> {
+    // chats created before the ready flag, content references, schemas, visibility, access
+    // policies and rate limits existed have to be grown before any other instruction loads them
+    pub fn migrate_chat(&mut self) -> Result<()> {
+        ChatContext::grow(&self.chat_context, &self.payer, &self.system_program)
+    }
+}
@@ -0,0 +1,21 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::OracleError, Config, ADMIN_IDENTITY};
+
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    #[account(mut, address = ADMIN_IDENTITY @ OracleError::InvalidAdmin)]
+    pub admin: Signer<'info>,
+    /// CHECK: a legacy config doesn't deserialize until it's grown
+    #[account(mut, seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl MigrateConfig<'_> {
+    // every instruction that loads the config rejects the legacy layout, so this has to run
+    // right after upgrading the program
+    pub fn migrate_config(&mut self) -> Result<()> {
+        Config::grow(&self.config, &self.admin, &self.system_program)
+    }
+}
@@ -1,13 +1,101 @@
+pub mod append_chat_text;
+pub mod append_inference_text;
+pub mod callback_batch;
+pub mod callback_from_buffer;
 pub mod callback_from_llm;
 pub mod callback_test;
+pub mod cancel_inference;
+pub mod challenge_response;
+pub mod close_batch;
+pub mod close_response_buffer;
+pub mod close_schedule;
+pub mod commit_response;
 pub mod create_chat;
 pub mod create_llm_inference;
+pub mod create_llm_inference_batch;
+pub mod create_schedule;
+pub mod create_workflow;
 pub mod delegate;
+pub mod deregister_oracle;
+pub mod expire_quorum;
+pub mod fail_batch;
+pub mod fail_inference;
+pub mod finalize_chat;
+pub mod finalize_inference;
+pub mod finalize_response;
+pub mod fund_schedule;
+pub mod init_response_buffer;
 pub mod initialize;
+pub mod migrate_chat;
+pub mod migrate_config;
+pub mod propose_response;
+pub mod record_workflow_step;
+pub mod refund_challenge;
+pub mod register_oracle;
+pub mod resolve_challenge;
+pub mod reveal_response;
+pub mod run_schedule;
+pub mod set_chat_access_policy;
+pub mod set_chat_content_ref;
+pub mod set_chat_rate_limit;
+pub mod set_chat_response_schema;
+pub mod set_chat_visibility;
+pub mod set_default_rate_limit;
+pub mod set_paused;
+pub mod slash_oracle;
+pub mod stake_oracle;
+pub mod submit_response;
+pub mod unstake_oracle;
+pub mod withdraw_stake;
+pub mod write_response_chunk;
 
+pub use append_chat_text::*;
+pub use append_inference_text::*;
+pub use callback_batch::*;
+pub use callback_from_buffer::*;
 pub use callback_from_llm::*;
 pub use callback_test::*;
+pub use cancel_inference::*;
+pub use challenge_response::*;
+pub use close_batch::*;
+pub use close_response_buffer::*;
+pub use close_schedule::*;
+pub use commit_response::*;
 pub use create_chat::*;
 pub use create_llm_inference::*;
+pub use create_llm_inference_batch::*;
+pub use create_schedule::*;
+pub use create_workflow::*;
 pub use delegate::*;
+pub use deregister_oracle::*;
+pub use expire_quorum::*;
+pub use fail_batch::*;
+pub use fail_inference::*;
+pub use finalize_chat::*;
+pub use finalize_inference::*;
+pub use finalize_response::*;
+pub use fund_schedule::*;
+pub use init_response_buffer::*;
 pub use initialize::*;
+pub use migrate_chat::*;
+pub use migrate_config::*;
+pub use propose_response::*;
+pub use record_workflow_step::*;
+pub use refund_challenge::*;
+pub use register_oracle::*;
+pub use resolve_challenge::*;
+pub use reveal_response::*;
+pub use run_schedule::*;
+pub use set_chat_access_policy::*;
+pub use set_chat_content_ref::*;
+pub use set_chat_rate_limit::*;
+pub use set_chat_response_schema::*;
+pub use set_chat_visibility::*;
+pub use set_default_rate_limit::*;
+pub use set_paused::*;
+pub use slash_oracle::*;
+pub use stake_oracle::*;
+pub use submit_response::*;
+pub use unstake_oracle::*;
+pub use withdraw_stake::*;
+pub use write_response_chunk::*;
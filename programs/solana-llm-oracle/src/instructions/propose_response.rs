@@ -0,0 +1,51 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{hash::hash, sysvar},
+};
+
+use crate::{error::OracleError, verify_attestation, Inference, ResponseBuffer, ORACLE_IDENTITY};
+
+#[derive(Accounts)]
+pub struct ProposeResponse<'info> {
+    #[account(address = ORACLE_IDENTITY)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        constraint = !inference.is_processed @ OracleError::InferenceProcessed,
+        constraint = inference.dispute_slots > 0 @ OracleError::DisputeWindowNotEnabled
+    )]
+    pub inference: Account<'info, Inference>,
+    #[account(
+        mut,
+        seeds = [ResponseBuffer::seed(), inference.key().as_ref()],
+        bump = response_buffer.bump,
+        constraint = response_buffer.nonce == inference.nonce @ OracleError::StaleResponse,
+        constraint = response_buffer.dispute_deadline == 0 @ OracleError::ResponseProposed
+    )]
+    pub response_buffer: Account<'info, ResponseBuffer>,
+    /// CHECK: instructions sysvar, the oracle's ed25519 attestation is read from it
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+}
+
+impl ProposeResponse<'_> {
+    // the attested response sits in the buffer and can be challenged until the deadline
+    pub fn propose_response(&mut self, model_id: String) -> Result<()> {
+        require!(self.inference.is_ready, OracleError::InferenceNotReady);
+
+        let response_hash = hash(&self.response_buffer.data).to_bytes();
+        require!(
+            response_hash == self.response_buffer.response_hash,
+            OracleError::ResponseHashMismatch
+        );
+
+        let attestation =
+            verify_attestation(&self.instructions, &self.inference, response_hash, model_id)?;
+        self.inference.attestation = Some(attestation);
+
+        self.response_buffer.dispute_deadline = Clock::get()?
+            .slot
+            .saturating_add(self.inference.dispute_slots);
+        Ok(())
+    }
+}
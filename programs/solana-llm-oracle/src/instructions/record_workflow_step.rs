@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::OracleError, events::WorkflowStepRecorded, Inference, Workflow, WorkflowRun,
+    ORACLE_IDENTITY,
+};
+
+#[derive(Accounts)]
+pub struct RecordWorkflowStep<'info> {
+    #[account(mut, address = ORACLE_IDENTITY)]
+    pub payer: Signer<'info>,
+    #[account(
+        constraint = !inference.is_processed @ OracleError::InferenceProcessed,
+        constraint = inference.workflow == Some(workflow.key()) @ OracleError::WorkflowMismatch
+    )]
+    pub inference: Account<'info, Inference>,
+    pub workflow: Account<'info, Workflow>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + WorkflowRun::INIT_SPACE,
+        seeds = [WorkflowRun::seed(), inference.key().as_ref()],
+        bump
+    )]
+    pub workflow_run: Account<'info, WorkflowRun>,
+    pub system_program: Program<'info, System>,
+}
+
+impl RecordWorkflowStep<'_> {
+    // keeps an intermediate response for audit, the last step goes through the regular callback.
+    // `response` may be cut short to fit the transaction, `response_hash` is of the full text
+    pub fn record_workflow_step(
+        &mut self,
+        step: u8,
+        response: String,
+        response_hash: [u8; 32],
+        bumps: &RecordWorkflowStepBumps,
+    ) -> Result<()> {
+        let run = &mut self.workflow_run;
+        if run.inference != self.inference.key() || run.nonce != self.inference.nonce {
+            run.set_inner(WorkflowRun {
+                inference: self.inference.key(),
+                nonce: self.inference.nonce,
+                workflow: self.workflow.key(),
+                step_hashes: Vec::new(),
+                bump: bumps.workflow_run,
+            });
+        }
+
+        // a restarted oracle runs the workflow again from the top, later steps get replaced
+        run.step_hashes.truncate(step as usize);
+        require!(
+            run.step_hashes.len() == step as usize
+                && (step as usize) + 1 < self.workflow.steps.len(),
+            OracleError::WorkflowStepOutOfOrder
+        );
+
+        run.step_hashes.push(response_hash);
+
+        emit!(WorkflowStepRecorded {
+            inference: self.inference.key(),
+            nonce: self.inference.nonce,
+            workflow: self.workflow.key(),
+            step,
+            response,
+            response_hash,
+            slot: Clock::get()?.slot,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,22 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::OracleError, Challenge, Inference};
+
+// a challenge that can't be resolved anymore, because the inference was re-requested or
+// settled some other way, hands the bond back to the challenger
+#[derive(Accounts)]
+pub struct RefundChallenge<'info> {
+    #[account(mut, address = challenge.challenger)]
+    pub challenger: Signer<'info>,
+    #[account(address = challenge.inference)]
+    pub inference: Account<'info, Inference>,
+    #[account(
+        mut,
+        close = challenger,
+        seeds = [Challenge::seed(), challenge.inference.as_ref(), challenge.nonce.to_le_bytes().as_ref()],
+        bump = challenge.bump,
+        constraint = challenge.nonce != inference.nonce
+            || inference.is_processed @ OracleError::ChallengePending
+    )]
+    pub challenge: Account<'info, Challenge>,
+}
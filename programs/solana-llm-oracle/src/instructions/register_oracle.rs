@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::OracleError, OracleNode, ADMIN_IDENTITY};
+
+#[derive(Accounts)]
+#[instruction(identity: Pubkey)]
+pub struct RegisterOracle<'info> {
+    #[account(mut, address = ADMIN_IDENTITY @ OracleError::InvalidAdmin)]
+    pub admin: Signer<'info>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + OracleNode::INIT_SPACE,
+        seeds = [OracleNode::seed(), identity.as_ref()],
+        bump
+    )]
+    pub oracle_node: Account<'info, OracleNode>,
+    pub system_program: Program<'info, System>,
+}
+
+impl RegisterOracle<'_> {
+    pub fn register_oracle(&mut self, identity: Pubkey, bumps: &RegisterOracleBumps) -> Result<()> {
+        self.oracle_node.set_inner(OracleNode {
+            identity,
+            stake: 0,
+            unbonding: 0,
+            unbonding_slot: 0,
+            bump: bumps.oracle_node,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+use anchor_lang::{prelude::*, AccountsClose};
+
+use crate::{
+    error::OracleError, invoke_callback, mark_failed, Challenge, Config, Inference, OracleNode,
+    ResponseBuffer, ADMIN_IDENTITY, CHALLENGE_VOTES, MIN_ORACLE_STAKE, ORACLE_IDENTITY,
+};
+
+#[derive(Accounts)]
+pub struct ResolveChallenge<'info> {
+    pub resolver: Signer<'info>,
+    /// Only needed when a staked oracle votes instead of the admin.
+    #[account(
+        seeds = [OracleNode::seed(), resolver.key().as_ref()],
+        bump = oracle_node.bump
+    )]
+    pub oracle_node: Option<Account<'info, OracleNode>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        constraint = !inference.is_processed @ OracleError::InferenceProcessed
+    )]
+    pub inference: Account<'info, Inference>,
+    #[account(
+        mut,
+        seeds = [ResponseBuffer::seed(), inference.key().as_ref()],
+        bump = response_buffer.bump,
+        constraint = response_buffer.nonce == inference.nonce @ OracleError::StaleResponse
+    )]
+    pub response_buffer: Account<'info, ResponseBuffer>,
+    #[account(
+        mut,
+        seeds = [Challenge::seed(), inference.key().as_ref(), inference.nonce.to_le_bytes().as_ref()],
+        bump = challenge.bump
+    )]
+    pub challenge: Account<'info, Challenge>,
+    /// CHECK: gets the bond back when the challenge is upheld
+    #[account(mut, address = challenge.challenger)]
+    pub challenger: AccountInfo<'info>,
+    /// CHECK: paid for the response buffer, and gets the bond when the challenge is rejected
+    #[account(mut, address = ORACLE_IDENTITY)]
+    pub oracle: AccountInfo<'info>,
+    /// CHECK: the callback program; this ixn is just a proxy
+    #[account(address = inference.callback_program_id)]
+    pub program: AccountInfo<'info>,
+}
+
+impl<'info> ResolveChallenge<'info> {
+    /// The admin settles a challenge right away, staked oracles other than the proposer
+    /// vote until `CHALLENGE_VOTES` of them agree. An upheld challenge fails the
+    /// inference, a rejected one fires the callback with the proposed response.
+    pub fn resolve_challenge(
+        &mut self,
+        uphold: bool,
+        remaining_accounts: Vec<AccountInfo<'info>>,
+    ) -> Result<()> {
+        let resolver = self.resolver.key();
+        let outcome = if resolver == ADMIN_IDENTITY {
+            Some(uphold)
+        } else {
+            let oracle_node = self
+                .oracle_node
+                .as_ref()
+                .ok_or(OracleError::InvalidResolver)?;
+            require!(
+                oracle_node.stake >= MIN_ORACLE_STAKE && resolver != ORACLE_IDENTITY,
+                OracleError::InvalidResolver
+            );
+
+            let challenge = &mut self.challenge;
+            require!(
+                !challenge.upheld_by.contains(&resolver)
+                    && !challenge.rejected_by.contains(&resolver),
+                OracleError::AlreadySubmitted
+            );
+            let votes = if uphold {
+                &mut challenge.upheld_by
+            } else {
+                &mut challenge.rejected_by
+            };
+            votes.push(resolver);
+            (votes.len() >= CHALLENGE_VOTES).then_some(uphold)
+        };
+
+        let Some(upheld) = outcome else {
+            return Ok(());
+        };
+
+        if upheld {
+            mark_failed(
+                &mut self.inference,
+                format!("Challenge upheld: {}", self.challenge.reason),
+            )?;
+            self.challenge.close(self.challenger.to_account_info())?;
+        } else {
+            let response = std::mem::take(&mut self.response_buffer.data);
+            invoke_callback(
+                &self.config,
+                &mut self.inference,
+                &self.program,
+                &response,
+                remaining_accounts,
+                &self.oracle,
+            )?;
+            self.challenge.close(self.oracle.to_account_info())?;
+        }
+        self.response_buffer.close(self.oracle.to_account_info())
+    }
+}
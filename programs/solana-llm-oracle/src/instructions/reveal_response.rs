@@ -0,0 +1,97 @@
+use anchor_lang::{prelude::*, solana_program::hash::hash};
+
+use crate::{
+    error::OracleError, numeric_value, settle_quorum, Config, Inference, OracleNode, Submissions,
+};
+
+#[derive(Accounts)]
+pub struct RevealResponse<'info> {
+    #[account(mut)]
+    pub oracle: Signer<'info>,
+    #[account(
+        seeds = [OracleNode::seed(), oracle.key().as_ref()],
+        bump = oracle_node.bump
+    )]
+    pub oracle_node: Account<'info, OracleNode>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        constraint = !inference.is_processed @ OracleError::InferenceProcessed
+    )]
+    pub inference: Account<'info, Inference>,
+    #[account(
+        mut,
+        seeds = [Submissions::seed(), inference.key().as_ref()],
+        bump = submissions.bump,
+        constraint = submissions.nonce == inference.nonce @ OracleError::NotCommitted
+    )]
+    pub submissions: Account<'info, Submissions>,
+    /// CHECK: the callback program; this ixn is just a proxy
+    #[account(address = inference.callback_program_id)]
+    pub program: AccountInfo<'info>,
+}
+
+impl<'info> RevealResponse<'info> {
+    pub fn reveal_response(
+        &mut self,
+        response: Vec<u8>,
+        salt: [u8; 32],
+        remaining_accounts: Vec<AccountInfo<'info>>,
+    ) -> Result<()> {
+        let quorum = self
+            .inference
+            .quorum
+            .clone()
+            .ok_or(OracleError::QuorumNotEnabled)?;
+        require!(
+            response.len() <= self.inference.max_response_len as usize,
+            OracleError::ResponseTooLarge
+        );
+
+        // reveals only open once nobody can commit anymore, so nothing revealed can be copied
+        let slot = Clock::get()?.slot;
+        require!(
+            slot > self.submissions.commit_deadline && slot <= self.submissions.reveal_deadline,
+            OracleError::RevealWindowNotOpen
+        );
+
+        let oracle = self.oracle.key();
+        let entry = self
+            .submissions
+            .entries
+            .iter_mut()
+            .find(|entry| entry.oracle == oracle)
+            .ok_or(OracleError::NotCommitted)?;
+        require!(!entry.revealed, OracleError::AlreadySubmitted);
+        require!(
+            entry.commitment
+                == Submissions::commitment(
+                    &oracle,
+                    &self.inference.key(),
+                    self.inference.nonce,
+                    &response,
+                    &salt
+                ),
+            OracleError::CommitmentMismatch
+        );
+
+        entry.revealed = true;
+        entry.response_hash = hash(&response).to_bytes();
+        entry.value = numeric_value(self.inference.output_type.as_ref(), &response);
+
+        settle_quorum(
+            &self.config,
+            &mut self.inference,
+            &mut self.submissions,
+            &quorum,
+            &self.program,
+            response,
+            remaining_accounts,
+            &self.oracle.to_account_info(),
+        )
+    }
+}
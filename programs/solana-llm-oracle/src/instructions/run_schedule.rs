@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+
+use crate::error::OracleError;
+use crate::events::{InferenceRequested, ScheduleRan};
+use crate::{ChatContext, Config, Inference, Schedule, ORACLE_IDENTITY, SCHEDULE_RUN_FEE};
+
+#[derive(Accounts)]
+pub struct RunSchedule<'info> {
+    #[account(mut, address = ORACLE_IDENTITY)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [Schedule::seed(), schedule.user.as_ref(), schedule.chat_context.as_ref(), schedule.schedule_id.to_le_bytes().as_ref()],
+        bump = schedule.bump
+    )]
+    pub schedule: Account<'info, Schedule>,
+    #[account(
+        address = schedule.chat_context,
+        constraint = chat_context.is_ready @ OracleError::ChatContextNotReady
+    )]
+    pub chat_context: Account<'info, ChatContext>,
+    #[account(
+        mut,
+        seeds = [Inference::seed(), schedule.key().as_ref(), chat_context.key().as_ref()],
+        bump
+    )]
+    pub inference: Account<'info, Inference>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.requests_paused @ OracleError::RequestsPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+impl RunSchedule<'_> {
+    // fires the next run as a regular inference, the oracle answers it like any other
+    pub fn run_schedule(&mut self) -> Result<()> {
+        let clock = Clock::get()?;
+        let schedule = &mut self.schedule;
+        require!(schedule.is_funded(), OracleError::ScheduleExhausted);
+        require!(
+            schedule.is_due(&clock) && self.inference.is_processed,
+            OracleError::ScheduleNotDue
+        );
+
+        schedule.balance -= SCHEDULE_RUN_FEE;
+        schedule.runs += 1;
+        schedule.last_run_slot = clock.slot;
+        schedule.last_run_timestamp = clock.unix_timestamp;
+        schedule.sub_lamports(SCHEDULE_RUN_FEE)?;
+        self.payer.add_lamports(SCHEDULE_RUN_FEE)?;
+
+        let inference = &mut self.inference;
+        inference.nonce = inference.nonce.wrapping_add(1);
+        inference.requested_slot = clock.slot;
+        inference.is_ready = true;
+        inference.is_processed = false;
+        inference.is_failed = false;
+        inference.attestation = None;
+
+        emit!(ScheduleRan {
+            user: schedule.user,
+            schedule: schedule.key(),
+            inference: inference.key(),
+            run: schedule.runs,
+            fee: SCHEDULE_RUN_FEE,
+            slot: clock.slot,
+        });
+        emit!(InferenceRequested {
+            user: inference.user,
+            chat_context: inference.chat_context,
+            inference: inference.key(),
+            nonce: inference.nonce,
+            callback_program: inference.callback_program_id,
+            slot: clock.slot,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::OracleError, AccessPolicy, ChatContext, MAX_ALLOWLIST_LEN};
+
+#[derive(Accounts)]
+#[instruction(access_policy: AccessPolicy)]
+pub struct SetChatAccessPolicy<'info> {
+    pub user: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"chat_context", user.key().as_ref(), chat_context.seed.to_le_bytes().as_ref()],
+        bump = chat_context.bump,
+        realloc = ChatContext::space(
+            &chat_context.text,
+            chat_context.content_ref.as_ref(),
+            chat_context.response_schema.as_ref(),
+            &chat_context.visibility,
+            &access_policy
+        ),
+        realloc::payer = payer,
+        realloc::zero = false
+    )]
+    pub chat_context: Account<'info, ChatContext>,
+    pub system_program: Program<'info, System>,
+}
+
+impl SetChatAccessPolicy<'_> {
+    // the default policy lifts every restriction
+    pub fn set_chat_access_policy(&mut self, access_policy: AccessPolicy) -> Result<()> {
+        require!(
+            access_policy.callback_programs.len() <= MAX_ALLOWLIST_LEN
+                && access_policy.requesters.len() <= MAX_ALLOWLIST_LEN,
+            OracleError::AllowlistTooLong
+        );
+        self.chat_context.access_policy = access_policy;
+        Ok(())
+    }
+}
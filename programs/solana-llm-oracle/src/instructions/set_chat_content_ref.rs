@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+use crate::{ChatContext, ContentRef};
+
+#[derive(Accounts)]
+#[instruction(content_ref: Option<ContentRef>)]
+pub struct SetChatContentRef<'info> {
+    pub user: Signer<'info>,
+    /// Pays for a larger content reference, so a pda owner doesn't have to.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"chat_context", user.key().as_ref(), chat_context.seed.to_le_bytes().as_ref()],
+        bump = chat_context.bump,
+        realloc = ChatContext::space(
+            &chat_context.text,
+            content_ref.as_ref(),
+            chat_context.response_schema.as_ref(),
+            &chat_context.visibility,
+            &chat_context.access_policy
+        ),
+        realloc::payer = payer,
+        realloc::zero = false
+    )]
+    pub chat_context: Account<'info, ChatContext>,
+    pub system_program: Program<'info, System>,
+}
+
+impl SetChatContentRef<'_> {
+    // `None` goes back to the inline text
+    pub fn set_chat_content_ref(&mut self, content_ref: Option<ContentRef>) -> Result<()> {
+        self.chat_context.content_ref = content_ref;
+        Ok(())
+    }
+}
@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::OracleError, ChatContext, RateLimit};
+
+#[derive(Accounts)]
+pub struct SetChatRateLimit<'info> {
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"chat_context", user.key().as_ref(), chat_context.seed.to_le_bytes().as_ref()],
+        bump = chat_context.bump
+    )]
+    pub chat_context: Account<'info, ChatContext>,
+}
+
+impl SetChatRateLimit<'_> {
+    // `None` falls back to the config's default
+    pub fn set_chat_rate_limit(&mut self, rate_limit: Option<RateLimit>) -> Result<()> {
+        require!(
+            rate_limit.as_ref().is_none_or(RateLimit::is_valid),
+            OracleError::InvalidRateLimit
+        );
+        self.chat_context.rate_limit = rate_limit;
+        Ok(())
+    }
+}
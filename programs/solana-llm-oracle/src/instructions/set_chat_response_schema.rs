@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+use crate::{ChatContext, ResponseSchema};
+
+#[derive(Accounts)]
+#[instruction(response_schema: Option<ResponseSchema>)]
+pub struct SetChatResponseSchema<'info> {
+    pub user: Signer<'info>,
+    /// Pays for a larger schema, so a pda owner doesn't have to.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"chat_context", user.key().as_ref(), chat_context.seed.to_le_bytes().as_ref()],
+        bump = chat_context.bump,
+        realloc = ChatContext::space(
+            &chat_context.text,
+            chat_context.content_ref.as_ref(),
+            response_schema.as_ref(),
+            &chat_context.visibility,
+            &chat_context.access_policy
+        ),
+        realloc::payer = payer,
+        realloc::zero = false
+    )]
+    pub chat_context: Account<'info, ChatContext>,
+    pub system_program: Program<'info, System>,
+}
+
+impl SetChatResponseSchema<'_> {
+    // `None` goes back to free text answers
+    pub fn set_chat_response_schema(
+        &mut self,
+        response_schema: Option<ResponseSchema>,
+    ) -> Result<()> {
+        self.chat_context.response_schema = response_schema;
+        Ok(())
+    }
+}
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::OracleError, ChatContext, ChatVisibility, MAX_ALLOWLIST_LEN};
+
+#[derive(Accounts)]
+#[instruction(visibility: ChatVisibility)]
+pub struct SetChatVisibility<'info> {
+    pub user: Signer<'info>,
+    /// Pays for a longer allowlist, so a pda owner doesn't have to.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"chat_context", user.key().as_ref(), chat_context.seed.to_le_bytes().as_ref()],
+        bump = chat_context.bump,
+        realloc = ChatContext::space(
+            &chat_context.text,
+            chat_context.content_ref.as_ref(),
+            chat_context.response_schema.as_ref(),
+            &visibility,
+            &chat_context.access_policy
+        ),
+        realloc::payer = payer,
+        realloc::zero = false
+    )]
+    pub chat_context: Account<'info, ChatContext>,
+    pub system_program: Program<'info, System>,
+}
+
+impl SetChatVisibility<'_> {
+    // inferences already requested stay untouched, the check only runs on new requests
+    pub fn set_chat_visibility(&mut self, visibility: ChatVisibility) -> Result<()> {
+        if let ChatVisibility::Allowlist(users) = &visibility {
+            require!(
+                users.len() <= MAX_ALLOWLIST_LEN,
+                OracleError::AllowlistTooLong
+            );
+        }
+        self.chat_context.visibility = visibility;
+        Ok(())
+    }
+}
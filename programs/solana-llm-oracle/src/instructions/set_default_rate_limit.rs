@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::OracleError, Config, RateLimit, ADMIN_IDENTITY};
+
+#[derive(Accounts)]
+pub struct SetDefaultRateLimit<'info> {
+    #[account(mut, address = ADMIN_IDENTITY @ OracleError::InvalidAdmin)]
+    pub admin: Signer<'info>,
+    /// CHECK: loaded after growing it to the current layout
+    #[account(mut, seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl SetDefaultRateLimit<'_> {
+    // `None` leaves chat contexts without a limit of their own unlimited
+    pub fn set_default_rate_limit(&mut self, rate_limit: Option<RateLimit>) -> Result<()> {
+        require!(
+            rate_limit.as_ref().is_none_or(RateLimit::is_valid),
+            OracleError::InvalidRateLimit
+        );
+        Config::grow(&self.config, &self.admin, &self.system_program)?;
+
+        let mut data = self.config.try_borrow_mut_data()?;
+        let mut config = Config::try_deserialize(&mut data.as_ref())?;
+        config.default_rate_limit = rate_limit;
+        config.try_serialize(&mut data.as_mut())?;
+        Ok(())
+    }
+}
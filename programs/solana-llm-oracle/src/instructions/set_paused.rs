@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::OracleError, events::PauseUpdated, Config, ADMIN_IDENTITY};
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(mut, address = ADMIN_IDENTITY @ OracleError::InvalidAdmin)]
+    pub admin: Signer<'info>,
+    /// CHECK: loaded after growing it to the current layout
+    #[account(mut, seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl SetPaused<'_> {
+    // emergency stop, requests and callbacks can be paused independently so pending
+    // inferences can still be answered, or held back, while nothing new comes in
+    pub fn set_paused(&mut self, requests_paused: bool, callbacks_paused: bool) -> Result<()> {
+        Config::grow(&self.config, &self.admin, &self.system_program)?;
+
+        let mut data = self.config.try_borrow_mut_data()?;
+        let mut config = Config::try_deserialize(&mut data.as_ref())?;
+        config.requests_paused = requests_paused;
+        config.callbacks_paused = callbacks_paused;
+        config.try_serialize(&mut data.as_mut())?;
+
+        emit!(PauseUpdated {
+            requests_paused,
+            callbacks_paused,
+            slot: Clock::get()?.slot,
+        });
+        Ok(())
+    }
+}
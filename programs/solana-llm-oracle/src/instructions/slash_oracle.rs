@@ -0,0 +1,85 @@
+use anchor_lang::{prelude::*, solana_program::sysvar};
+
+use crate::{
+    error::OracleError, verify_ed25519, Attestation, Inference, Offense, OracleNode, Submissions,
+    ADMIN_IDENTITY,
+};
+
+#[derive(Accounts)]
+pub struct SlashOracle<'info> {
+    #[account(mut, address = ADMIN_IDENTITY @ OracleError::InvalidAdmin)]
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [OracleNode::seed(), oracle_node.identity.as_ref()],
+        bump = oracle_node.bump
+    )]
+    pub oracle_node: Account<'info, OracleNode>,
+    pub inference: Account<'info, Inference>,
+    #[account(
+        mut,
+        seeds = [Submissions::seed(), inference.key().as_ref()],
+        bump = submissions.bump
+    )]
+    pub submissions: Account<'info, Submissions>,
+    /// CHECK: instructions sysvar, a contradicting attestation is read from it
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+}
+
+impl SlashOracle<'_> {
+    /// Takes up to `amount` lamports from the oracle's stake, unbonding lamports included,
+    /// once the offense checks out against the submissions of the request. Each submission
+    /// can be slashed once, for at most `Offense::max_slash`.
+    pub fn slash_oracle(&mut self, offense: Offense, amount: u64) -> Result<()> {
+        require!(
+            amount <= offense.max_slash(),
+            OracleError::InvalidSlashAmount
+        );
+        let identity = self.oracle_node.identity;
+        let inference = self.inference.key();
+        let nonce = self.submissions.nonce;
+        let reveal_deadline = self.submissions.reveal_deadline;
+        let entry = self
+            .submissions
+            .entries
+            .iter_mut()
+            .find(|entry| entry.oracle == identity)
+            .ok_or(OracleError::InvalidOffense)?;
+        require!(!entry.slashed, OracleError::AlreadySlashed);
+
+        match offense {
+            Offense::ContradictingAttestation {
+                response_hash,
+                model_id,
+            } => {
+                require!(
+                    entry.revealed && entry.response_hash != response_hash,
+                    OracleError::InvalidOffense
+                );
+                let message = Attestation::message(&inference, nonce, &response_hash, &model_id);
+                verify_ed25519(&self.instructions, &identity, &message)?;
+            }
+            Offense::MissedReveal => {
+                // reveal_deadline stays 0 for quorums without commit-reveal
+                require!(
+                    !entry.revealed && reveal_deadline > 0 && Clock::get()?.slot > reveal_deadline,
+                    OracleError::InvalidOffense
+                );
+            }
+        }
+        entry.slashed = true;
+
+        let oracle_node = &mut self.oracle_node;
+        let from_stake = amount.min(oracle_node.stake);
+        let from_unbonding = (amount - from_stake).min(oracle_node.unbonding);
+        oracle_node.stake -= from_stake;
+        oracle_node.unbonding -= from_unbonding;
+
+        let slashed = from_stake + from_unbonding;
+        msg!("Slashed {} lamports from oracle {}", slashed, identity);
+        oracle_node.sub_lamports(slashed)?;
+        self.admin.add_lamports(slashed)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,35 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+
+use crate::OracleNode;
+
+#[derive(Accounts)]
+pub struct StakeOracle<'info> {
+    #[account(mut)]
+    pub oracle: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [OracleNode::seed(), oracle.key().as_ref()],
+        bump = oracle_node.bump
+    )]
+    pub oracle_node: Account<'info, OracleNode>,
+    pub system_program: Program<'info, System>,
+}
+
+impl StakeOracle<'_> {
+    pub fn stake_oracle(&mut self, amount: u64) -> Result<()> {
+        let cpi_context = CpiContext::new(
+            self.system_program.to_account_info(),
+            Transfer {
+                from: self.oracle.to_account_info(),
+                to: self.oracle_node.to_account_info(),
+            },
+        );
+        transfer(cpi_context, amount)?;
+
+        self.oracle_node.stake = self.oracle_node.stake.saturating_add(amount);
+        Ok(())
+    }
+}
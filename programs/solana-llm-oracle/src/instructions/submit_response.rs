@@ -0,0 +1,179 @@
+use anchor_lang::{prelude::*, solana_program::hash::hash};
+
+use crate::{
+    encode_numeric, error::OracleError, invoke_callback, mark_failed, numeric_value, AgreementMode,
+    Config, Inference, OracleNode, QuorumConfig, Submission, Submissions, MIN_ORACLE_STAKE,
+};
+
+#[derive(Accounts)]
+pub struct SubmitResponse<'info> {
+    #[account(mut)]
+    pub oracle: Signer<'info>,
+    #[account(
+        seeds = [OracleNode::seed(), oracle.key().as_ref()],
+        bump = oracle_node.bump,
+        constraint = oracle_node.stake >= MIN_ORACLE_STAKE @ OracleError::InsufficientStake
+    )]
+    pub oracle_node: Account<'info, OracleNode>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        constraint = !inference.is_processed @ OracleError::InferenceProcessed
+    )]
+    pub inference: Account<'info, Inference>,
+    #[account(
+        init_if_needed,
+        payer = oracle,
+        space = 8 + Submissions::INIT_SPACE,
+        seeds = [Submissions::seed(), inference.key().as_ref()],
+        bump
+    )]
+    pub submissions: Account<'info, Submissions>,
+    /// CHECK: the callback program; this ixn is just a proxy
+    #[account(address = inference.callback_program_id)]
+    pub program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SubmitResponse<'info> {
+    pub fn submit_response(
+        &mut self,
+        response: Vec<u8>,
+        remaining_accounts: Vec<AccountInfo<'info>>,
+        bumps: &SubmitResponseBumps,
+    ) -> Result<()> {
+        let quorum = self
+            .inference
+            .quorum
+            .clone()
+            .ok_or(OracleError::QuorumNotEnabled)?;
+        require!(
+            quorum.commit_reveal.is_none(),
+            OracleError::CommitRevealRequired
+        );
+        require!(
+            response.len() <= self.inference.max_response_len as usize,
+            OracleError::ResponseTooLarge
+        );
+
+        reset_submissions(&mut self.submissions, &self.inference, bumps.submissions);
+        let oracle = self.oracle.key();
+        require!(
+            !self
+                .submissions
+                .entries
+                .iter()
+                .any(|entry| entry.oracle == oracle),
+            OracleError::AlreadySubmitted
+        );
+
+        self.submissions.entries.push(Submission {
+            oracle,
+            commitment: [0; 32],
+            revealed: true,
+            response_hash: hash(&response).to_bytes(),
+            value: numeric_value(self.inference.output_type.as_ref(), &response),
+            slashed: false,
+        });
+
+        settle_quorum(
+            &self.config,
+            &mut self.inference,
+            &mut self.submissions,
+            &quorum,
+            &self.program,
+            response,
+            remaining_accounts,
+            &self.oracle.to_account_info(),
+        )
+    }
+}
+
+// the pda outlives a request, submissions to an earlier nonce don't count
+pub fn reset_submissions(submissions: &mut Submissions, inference: &Account<Inference>, bump: u8) {
+    if submissions.inference == inference.key() && submissions.nonce == inference.nonce {
+        return;
+    }
+
+    let (commit_deadline, reveal_deadline) = inference
+        .quorum
+        .as_ref()
+        .and_then(|quorum| quorum.commit_reveal)
+        .map_or((0, 0), |windows| {
+            let commit_deadline = inference
+                .requested_slot
+                .saturating_add(windows.commit_slots);
+            (
+                commit_deadline,
+                commit_deadline.saturating_add(windows.reveal_slots),
+            )
+        });
+
+    *submissions = Submissions {
+        inference: inference.key(),
+        nonce: inference.nonce,
+        commit_deadline,
+        reveal_deadline,
+        entries: Vec::new(),
+        agreed: Vec::new(),
+        dissented: Vec::new(),
+        bump,
+    };
+}
+
+/// Checks the revealed responses for agreement after `response` was added, fires the
+/// callback once `threshold` oracles agree and fails the inference when they never can.
+#[allow(clippy::too_many_arguments)]
+pub fn settle_quorum<'info>(
+    config: &Account<'info, Config>,
+    inference: &mut Account<'info, Inference>,
+    submissions: &mut Submissions,
+    quorum: &QuorumConfig,
+    program: &AccountInfo<'info>,
+    response: Vec<u8>,
+    remaining_accounts: Vec<AccountInfo<'info>>,
+    fee_recipient: &AccountInfo<'info>,
+) -> Result<()> {
+    let (agreed_response, agreed) = match quorum.mode {
+        AgreementMode::Exact => {
+            let agreed = submissions.exact_agreement(&hash(&response).to_bytes());
+            (response, agreed)
+        }
+        AgreementMode::Median => match submissions.median_agreement(quorum.tolerance) {
+            Some((median, agreed)) => (
+                encode_numeric(inference.output_type.as_ref(), median),
+                agreed,
+            ),
+            None => (response, Vec::new()),
+        },
+    };
+
+    if agreed.len() < quorum.threshold as usize {
+        let all_revealed = submissions.entries.iter().all(|entry| entry.revealed);
+        if submissions.entries.len() >= quorum.size as usize && all_revealed {
+            mark_failed(inference, "Quorum not reached".to_string())?;
+        }
+        return Ok(());
+    }
+
+    submissions.dissented = submissions
+        .entries
+        .iter()
+        .map(|entry| entry.oracle)
+        .filter(|oracle| !agreed.contains(oracle))
+        .collect();
+    submissions.agreed = agreed;
+
+    invoke_callback(
+        config,
+        inference,
+        program,
+        &agreed_response,
+        remaining_accounts,
+        fee_recipient,
+    )
+}
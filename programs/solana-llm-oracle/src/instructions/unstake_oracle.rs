@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::OracleError, OracleNode, UNBONDING_SLOTS};
+
+#[derive(Accounts)]
+pub struct UnstakeOracle<'info> {
+    pub oracle: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [OracleNode::seed(), oracle.key().as_ref()],
+        bump = oracle_node.bump
+    )]
+    pub oracle_node: Account<'info, OracleNode>,
+}
+
+impl UnstakeOracle<'_> {
+    // starts the unbonding delay over for everything unbonding, so nothing leaves while still slashable
+    pub fn unstake_oracle(&mut self, amount: u64) -> Result<()> {
+        let oracle_node = &mut self.oracle_node;
+        require!(amount <= oracle_node.stake, OracleError::InsufficientStake);
+
+        oracle_node.stake -= amount;
+        oracle_node.unbonding = oracle_node.unbonding.saturating_add(amount);
+        oracle_node.unbonding_slot = Clock::get()?.slot.saturating_add(UNBONDING_SLOTS);
+        Ok(())
+    }
+}
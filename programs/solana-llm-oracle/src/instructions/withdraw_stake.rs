@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::OracleError, OracleNode};
+
+#[derive(Accounts)]
+pub struct WithdrawStake<'info> {
+    #[account(mut)]
+    pub oracle: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [OracleNode::seed(), oracle.key().as_ref()],
+        bump = oracle_node.bump
+    )]
+    pub oracle_node: Account<'info, OracleNode>,
+}
+
+impl WithdrawStake<'_> {
+    pub fn withdraw_stake(&mut self) -> Result<()> {
+        require!(
+            Clock::get()?.slot >= self.oracle_node.unbonding_slot,
+            OracleError::StakeUnbonding
+        );
+
+        let amount = self.oracle_node.unbonding;
+        self.oracle_node.unbonding = 0;
+        self.oracle_node.sub_lamports(amount)?;
+        self.oracle.add_lamports(amount)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::OracleError, ResponseBuffer, ORACLE_IDENTITY};
+
+#[derive(Accounts)]
+pub struct WriteResponseChunk<'info> {
+    #[account(address = ORACLE_IDENTITY)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ResponseBuffer::seed(), response_buffer.inference.as_ref()],
+        bump = response_buffer.bump,
+        constraint = response_buffer.dispute_deadline == 0 @ OracleError::ResponseProposed
+    )]
+    pub response_buffer: Account<'info, ResponseBuffer>,
+}
+
+impl WriteResponseChunk<'_> {
+    pub fn write_response_chunk(&mut self, offset: u32, chunk: Vec<u8>) -> Result<()> {
+        let start = offset as usize;
+        let end = start
+            .checked_add(chunk.len())
+            .ok_or(OracleError::ResponseChunkOutOfBounds)?;
+        require!(
+            end <= self.response_buffer.data.len(),
+            OracleError::ResponseChunkOutOfBounds
+        );
+
+        self.response_buffer.data[start..end].copy_from_slice(&chunk);
+        Ok(())
+    }
+}
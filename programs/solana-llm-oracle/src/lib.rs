@@ -1,5 +1,6 @@
 pub mod constants;
 pub mod error;
+pub mod events;
 pub mod instructions;
 pub mod state;
 
@@ -27,40 +28,325 @@ pub mod solana_llm_oracle {
         ctx.accounts.create_new_chat(text, seed, &ctx.bumps)
     }
 
+    pub fn migrate_chat(ctx: Context<MigrateChat>, _seed: u8) -> Result<()> {
+        ctx.accounts.migrate_chat()
+    }
+
+    pub fn append_chat_text(ctx: Context<AppendChatText>, text: String) -> Result<()> {
+        ctx.accounts.append_chat_text(text)
+    }
+
+    pub fn finalize_chat(ctx: Context<FinalizeChat>) -> Result<()> {
+        ctx.accounts.finalize_chat()
+    }
+
+    pub fn set_chat_content_ref(
+        ctx: Context<SetChatContentRef>,
+        content_ref: Option<ContentRef>,
+    ) -> Result<()> {
+        ctx.accounts.set_chat_content_ref(content_ref)
+    }
+
+    pub fn set_chat_response_schema(
+        ctx: Context<SetChatResponseSchema>,
+        response_schema: Option<ResponseSchema>,
+    ) -> Result<()> {
+        ctx.accounts.set_chat_response_schema(response_schema)
+    }
+
+    pub fn set_chat_visibility(
+        ctx: Context<SetChatVisibility>,
+        visibility: ChatVisibility,
+    ) -> Result<()> {
+        ctx.accounts.set_chat_visibility(visibility)
+    }
+
+    pub fn set_chat_access_policy(
+        ctx: Context<SetChatAccessPolicy>,
+        access_policy: AccessPolicy,
+    ) -> Result<()> {
+        ctx.accounts.set_chat_access_policy(access_policy)
+    }
+
+    pub fn set_chat_rate_limit(
+        ctx: Context<SetChatRateLimit>,
+        rate_limit: Option<RateLimit>,
+    ) -> Result<()> {
+        ctx.accounts.set_chat_rate_limit(rate_limit)
+    }
+
+    pub fn set_default_rate_limit(
+        ctx: Context<SetDefaultRateLimit>,
+        rate_limit: Option<RateLimit>,
+    ) -> Result<()> {
+        ctx.accounts.set_default_rate_limit(rate_limit)
+    }
+
+    pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+        ctx.accounts.migrate_config()
+    }
+
+    pub fn set_paused(
+        ctx: Context<SetPaused>,
+        requests_paused: bool,
+        callbacks_paused: bool,
+    ) -> Result<()> {
+        ctx.accounts.set_paused(requests_paused, callbacks_paused)
+    }
+
     pub fn create_llm_inference(
         ctx: Context<CreateLlmInference>,
         text: String,
         callback_program_id: Pubkey,
         callback_discriminator: [u8; 8],
         account_metas: Option<Vec<AccountMeta>>,
+        options: Option<InferenceOptions>,
     ) -> Result<()> {
         ctx.accounts.create_llm_inference(
             text,
             callback_program_id,
             callback_discriminator,
             account_metas,
+            options,
+            &ctx.bumps,
+        )
+    }
+
+    pub fn create_llm_inference_batch(
+        ctx: Context<CreateLlmInferenceBatch>,
+        batch_id: u64,
+        prompts: Vec<String>,
+        callback_program_id: Pubkey,
+        callback_discriminator: [u8; 8],
+        account_metas: Option<Vec<AccountMeta>>,
+        config: BatchConfig,
+    ) -> Result<()> {
+        ctx.accounts.create_llm_inference_batch(
+            batch_id,
+            prompts,
+            callback_program_id,
+            callback_discriminator,
+            account_metas,
+            config,
             &ctx.bumps,
         )
     }
 
+    pub fn append_inference_text(ctx: Context<AppendInferenceText>, text: String) -> Result<()> {
+        ctx.accounts.append_inference_text(text)
+    }
+
+    pub fn finalize_inference(ctx: Context<FinalizeInference>) -> Result<()> {
+        ctx.accounts.finalize_inference()
+    }
+
+    pub fn create_workflow(
+        ctx: Context<CreateWorkflow>,
+        seed: u8,
+        steps: Vec<String>,
+    ) -> Result<()> {
+        ctx.accounts.create_workflow(seed, steps, &ctx.bumps)
+    }
+
+    pub fn record_workflow_step(
+        ctx: Context<RecordWorkflowStep>,
+        step: u8,
+        response: String,
+        response_hash: [u8; 32],
+    ) -> Result<()> {
+        ctx.accounts
+            .record_workflow_step(step, response, response_hash, &ctx.bumps)
+    }
+
     pub fn delegate(ctx: Context<Delegate>) -> Result<()> {
         ctx.accounts.delegate()
     }
 
     pub fn callback_from_llm<'info>(
         ctx: Context<'_, '_, '_, 'info, CallbackFromLlm<'info>>,
+        response: Vec<u8>,
+        model_id: String,
+    ) -> Result<()> {
+        ctx.accounts
+            .callback_from_llm(response, model_id, ctx.remaining_accounts.to_vec())
+    }
+
+    pub fn init_response_buffer(
+        ctx: Context<InitResponseBuffer>,
+        total_len: u32,
+        response_hash: [u8; 32],
+    ) -> Result<()> {
+        ctx.accounts
+            .init_response_buffer(total_len, response_hash, &ctx.bumps)
+    }
+
+    pub fn write_response_chunk(
+        ctx: Context<WriteResponseChunk>,
+        offset: u32,
+        chunk: Vec<u8>,
+    ) -> Result<()> {
+        ctx.accounts.write_response_chunk(offset, chunk)
+    }
+
+    pub fn callback_from_buffer<'info>(
+        ctx: Context<'_, '_, '_, 'info, CallbackFromBuffer<'info>>,
+        model_id: String,
+    ) -> Result<()> {
+        ctx.accounts
+            .callback_from_buffer(model_id, ctx.remaining_accounts.to_vec())
+    }
+
+    pub fn callback_batch_item<'info>(
+        ctx: Context<'_, '_, '_, 'info, CallbackBatch<'info>>,
+        index: u16,
         response: String,
     ) -> Result<()> {
-        // Check if payer is not in remaining accounts, oracle also sends callback_account_metas from client which are remaining accounts
-        if ctx
-            .remaining_accounts
-            .iter()
-            .any(|acc| acc.key().eq(&ctx.accounts.config.key()))
-        {
-            return Err(ProgramError::InvalidAccountData.into());
-        }
         ctx.accounts
-            .callback_from_llm(response, ctx.remaining_accounts.to_vec())
+            .callback_batch_item(index, response, ctx.remaining_accounts.to_vec())
+    }
+
+    pub fn callback_batch<'info>(
+        ctx: Context<'_, '_, '_, 'info, CallbackBatch<'info>>,
+        responses: Vec<String>,
+    ) -> Result<()> {
+        ctx.accounts
+            .callback_batch(responses, ctx.remaining_accounts.to_vec())
+    }
+
+    pub fn propose_response(ctx: Context<ProposeResponse>, model_id: String) -> Result<()> {
+        ctx.accounts.propose_response(model_id)
+    }
+
+    pub fn challenge_response(ctx: Context<ChallengeResponse>, reason: String) -> Result<()> {
+        ctx.accounts.challenge_response(reason, &ctx.bumps)
+    }
+
+    pub fn finalize_response<'info>(
+        ctx: Context<'_, '_, '_, 'info, FinalizeResponse<'info>>,
+    ) -> Result<()> {
+        ctx.accounts
+            .finalize_response(ctx.remaining_accounts.to_vec())
+    }
+
+    pub fn resolve_challenge<'info>(
+        ctx: Context<'_, '_, '_, 'info, ResolveChallenge<'info>>,
+        uphold: bool,
+    ) -> Result<()> {
+        ctx.accounts
+            .resolve_challenge(uphold, ctx.remaining_accounts.to_vec())
+    }
+
+    pub fn refund_challenge(_ctx: Context<RefundChallenge>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn close_response_buffer(_ctx: Context<CloseResponseBuffer>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn fail_batch(ctx: Context<FailBatch>, reason: String) -> Result<()> {
+        ctx.accounts.fail_batch(reason)
+    }
+
+    pub fn close_batch(_ctx: Context<CloseBatch>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn create_schedule(
+        ctx: Context<CreateSchedule>,
+        schedule_id: u64,
+        prompt: String,
+        callback_program_id: Pubkey,
+        callback_discriminator: [u8; 8],
+        account_metas: Option<Vec<AccountMeta>>,
+        config: ScheduleConfig,
+    ) -> Result<()> {
+        ctx.accounts.create_schedule(
+            schedule_id,
+            prompt,
+            callback_program_id,
+            callback_discriminator,
+            account_metas,
+            config,
+            &ctx.bumps,
+        )
+    }
+
+    pub fn fund_schedule(
+        ctx: Context<FundSchedule>,
+        amount: u64,
+        additional_runs: u32,
+    ) -> Result<()> {
+        ctx.accounts.fund_schedule(amount, additional_runs)
+    }
+
+    pub fn run_schedule(ctx: Context<RunSchedule>) -> Result<()> {
+        ctx.accounts.run_schedule()
+    }
+
+    pub fn close_schedule(_ctx: Context<CloseSchedule>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn cancel_inference(ctx: Context<CancelInference>) -> Result<()> {
+        ctx.accounts.cancel_inference()
+    }
+
+    pub fn fail_inference(ctx: Context<FailInference>, reason: String) -> Result<()> {
+        ctx.accounts.fail_inference(reason)
+    }
+
+    pub fn register_oracle(ctx: Context<RegisterOracle>, identity: Pubkey) -> Result<()> {
+        ctx.accounts.register_oracle(identity, &ctx.bumps)
+    }
+
+    pub fn deregister_oracle(_ctx: Context<DeregisterOracle>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn stake_oracle(ctx: Context<StakeOracle>, amount: u64) -> Result<()> {
+        ctx.accounts.stake_oracle(amount)
+    }
+
+    pub fn unstake_oracle(ctx: Context<UnstakeOracle>, amount: u64) -> Result<()> {
+        ctx.accounts.unstake_oracle(amount)
+    }
+
+    pub fn withdraw_stake(ctx: Context<WithdrawStake>) -> Result<()> {
+        ctx.accounts.withdraw_stake()
+    }
+
+    pub fn slash_oracle(ctx: Context<SlashOracle>, offense: Offense, amount: u64) -> Result<()> {
+        ctx.accounts.slash_oracle(offense, amount)
+    }
+
+    pub fn submit_response<'info>(
+        ctx: Context<'_, '_, '_, 'info, SubmitResponse<'info>>,
+        response: Vec<u8>,
+    ) -> Result<()> {
+        ctx.accounts
+            .submit_response(response, ctx.remaining_accounts.to_vec(), &ctx.bumps)
+    }
+
+    pub fn commit_response(ctx: Context<CommitResponse>, commitment: [u8; 32]) -> Result<()> {
+        ctx.accounts.commit_response(commitment, &ctx.bumps)
+    }
+
+    pub fn reveal_response<'info>(
+        ctx: Context<'_, '_, '_, 'info, RevealResponse<'info>>,
+        response: Vec<u8>,
+        salt: [u8; 32],
+    ) -> Result<()> {
+        ctx.accounts
+            .reveal_response(response, salt, ctx.remaining_accounts.to_vec())
+    }
+
+    pub fn expire_quorum<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExpireQuorum<'info>>,
+        response: Option<Vec<u8>>,
+    ) -> Result<()> {
+        ctx.accounts
+            .expire_quorum(response, ctx.remaining_accounts.to_vec())
     }
 
     pub fn callback_test(ctx: Context<CallbackTest>, response: String) -> Result<()> {
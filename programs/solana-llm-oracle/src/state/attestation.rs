@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+
+/// The oracle's signed statement over a delivered response, kept on the
+/// inference so anyone can check it later without trusting the callback.
+#[derive(InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct Attestation {
+    pub response_hash: [u8; 32],
+    #[max_len(32)]
+    pub model_id: String,
+    pub signature: [u8; 64],
+}
+
+impl Attestation {
+    /// `inference || nonce (le) || sha256(response) || model_id`, the bytes the oracle signs.
+    pub fn message(
+        inference: &Pubkey,
+        nonce: u64,
+        response_hash: &[u8; 32],
+        model_id: &str,
+    ) -> Vec<u8> {
+        [
+            inference.as_ref(),
+            &nonce.to_le_bytes(),
+            response_hash,
+            model_id.as_bytes(),
+        ]
+        .concat()
+    }
+}
@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+
+use crate::state::AccountMeta;
+use crate::MAX_BATCH_RESPONSE_BYTES;
+
+/// How a batch's responses reach the callback.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BatchCallback {
+    /// One callback per prompt with the `u16` index and the response `String`.
+    #[default]
+    PerItem,
+    /// A single callback with every response as a `Vec<String>`, in prompt order.
+    Aggregated,
+}
+
+/// How a batch is called back, and how long each of its answers may be.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct BatchConfig {
+    pub callback_mode: BatchCallback,
+    /// Longest answer to a single prompt in bytes, longer ones are cut down to it.
+    pub max_response_len: u32,
+}
+
+impl BatchConfig {
+    // batch answers only go inline, so every callback has to fit in a transaction
+    pub fn is_valid(&self, size: usize) -> bool {
+        let items_per_callback = match self.callback_mode {
+            BatchCallback::PerItem => 1,
+            BatchCallback::Aggregated => size,
+        };
+        self.max_response_len > 0
+            && items_per_callback * (4 + self.max_response_len as usize) <= MAX_BATCH_RESPONSE_BYTES
+    }
+}
+
+/// Several prompts under one chat context, answered together by the oracle.
+#[account]
+pub struct Batch {
+    pub chat_context: Pubkey,
+    pub user: Pubkey,
+    pub batch_id: u64,
+    pub prompts: Vec<String>,
+    pub callback_program_id: Pubkey,
+    pub callback_discriminator: [u8; 8],
+    pub callback_account_metas: Vec<AccountMeta>,
+    pub callback_mode: BatchCallback,
+    pub max_response_len: u32,
+    /// Which prompts have been called back, by index.
+    pub fulfilled: Vec<bool>,
+    pub is_processed: bool,
+    pub is_failed: bool,
+    pub bump: u8,
+}
+
+impl Batch {
+    pub fn seed() -> &'static [u8] {
+        b"batch"
+    }
+
+    // 140 = 8 + 32 + 32 + 8 + 4 + 32 + 8 + 4 + 1 + 4 + 4 + 1 + 1 + 1
+    pub fn space(prompts: &[String], callback_account_metas: usize) -> usize {
+        140 + prompts
+            .iter()
+            .map(|prompt| 4 + prompt.len() + 1)
+            .sum::<usize>()
+            + callback_account_metas * AccountMeta::INIT_SPACE
+    }
+}
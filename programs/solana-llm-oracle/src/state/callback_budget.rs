@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+use crate::{CALLBACK_SIGNATURE_FEE, MAX_CALLBACK_COMPUTE_UNITS};
+
+/// Compute the requester asks the oracle to give the callback transaction, paid for
+/// upfront out of the fee escrowed on the inference.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CallbackBudget {
+    pub compute_unit_limit: u32,
+    /// Highest compute unit price the oracle may pay, in micro-lamports.
+    pub priority_fee_cap: u64,
+}
+
+impl CallbackBudget {
+    pub fn is_valid(&self) -> bool {
+        self.compute_unit_limit > 0 && self.compute_unit_limit <= MAX_CALLBACK_COMPUTE_UNITS
+    }
+
+    /// Most the callback transaction can cost the oracle, in lamports.
+    pub fn max_fee(&self) -> u64 {
+        let priority_fee = (self.compute_unit_limit as u64)
+            .saturating_mul(self.priority_fee_cap)
+            .div_ceil(1_000_000);
+        CALLBACK_SIGNATURE_FEE.saturating_add(priority_fee)
+    }
+}
@@ -0,0 +1,22 @@
+use anchor_lang::prelude::*;
+
+/// How the callback instruction is laid out.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CallbackFormat {
+    /// `config` then the requester's metas, the data is just the response.
+    #[default]
+    Plain,
+    /// `config`, the inference (read-only) then the requester's metas, the data starts
+    /// with a borsh `CallbackMetadata` ahead of the response.
+    WithMetadata,
+}
+
+/// Tells the callback which request it is answering, take it as the first argument of
+/// the callback when the inference uses `CallbackFormat::WithMetadata`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CallbackMetadata {
+    pub nonce: u64,
+    pub response_hash: [u8; 32],
+    /// Model the oracle attested to, empty for quorum responses which carry no attestation.
+    pub model_id: String,
+}
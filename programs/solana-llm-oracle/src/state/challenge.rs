@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+
+use crate::{CHALLENGE_VOTES, MAX_CHALLENGE_REASON_LEN};
+
+/// A bonded dispute of a proposed response, holds the bond until it is resolved.
+#[account]
+#[derive(InitSpace)]
+pub struct Challenge {
+    pub inference: Pubkey,
+    /// The request the challenged response answers, a newer one leaves the challenge stale.
+    pub nonce: u64,
+    pub challenger: Pubkey,
+    #[max_len(MAX_CHALLENGE_REASON_LEN)]
+    pub reason: String,
+    /// Staked oracles that voted the response wrong.
+    #[max_len(CHALLENGE_VOTES)]
+    pub upheld_by: Vec<Pubkey>,
+    /// Staked oracles that voted the response right.
+    #[max_len(CHALLENGE_VOTES)]
+    pub rejected_by: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+impl Challenge {
+    pub fn seed() -> &'static [u8] {
+        b"challenge"
+    }
+}
@@ -1,8 +1,174 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
 
+use crate::{ContentRef, RateLimit, ResponseSchema};
+
+/// Who besides the owner can send inferences to a chat context.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ChatVisibility {
+    /// Only the owner whose key the chat context is derived from.
+    #[default]
+    Private,
+    /// Anyone.
+    Public,
+    /// The owner and the listed users.
+    Allowlist(Vec<Pubkey>),
+}
+
+impl ChatVisibility {
+    pub fn space(&self) -> usize {
+        1 + match self {
+            ChatVisibility::Private | ChatVisibility::Public => 0,
+            ChatVisibility::Allowlist(users) => 4 + users.len() * 32,
+        }
+    }
+}
+
+/// Restricts which programs a chat context's inferences can involve, an empty list allows any.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccessPolicy {
+    /// Programs the oracle may call back with the chat's answers.
+    pub callback_programs: Vec<Pubkey>,
+    /// Programs that have to have requested the inference, see `InferenceOptions::requester_program`.
+    pub requesters: Vec<Pubkey>,
+}
+
+impl AccessPolicy {
+    pub fn space(&self) -> usize {
+        4 + self.callback_programs.len() * 32 + 4 + self.requesters.len() * 32
+    }
+
+    pub fn allows_callback(&self, program_id: &Pubkey) -> bool {
+        self.callback_programs.is_empty() || self.callback_programs.contains(program_id)
+    }
+
+    pub fn allows_requester(&self, requester_program: Option<&Pubkey>) -> bool {
+        self.requesters.is_empty()
+            || requester_program.is_some_and(|program| self.requesters.contains(program))
+    }
+}
+
+// newer fields go after `bump`, so chat contexts created before them can be grown in place
 #[account]
 pub struct ChatContext {
     pub text: String,
     pub seed: u8,
     pub bump: u8,
+    pub is_ready: bool,
+    pub content_ref: Option<ContentRef>,
+    /// Default schema for the agent's answers, an inference can bring its own.
+    pub response_schema: Option<ResponseSchema>,
+    pub visibility: ChatVisibility,
+    pub access_policy: AccessPolicy,
+    /// Overrides the config's default rate limit for this chat.
+    pub rate_limit: Option<RateLimit>,
+}
+
+/// The layout chat contexts were created with before `is_ready` and the fields after it.
+#[derive(AnchorDeserialize)]
+struct LegacyChatContext {
+    text: String,
+    seed: u8,
+    bump: u8,
+}
+
+impl ChatContext {
+    // 18 = 8 + 4 + 1 + 1 + 1 + 1 + 1 + 1
+    // room for a rate limit is reserved up front, setting one never has to grow the account
+    pub fn space(
+        text: &str,
+        content_ref: Option<&ContentRef>,
+        response_schema: Option<&ResponseSchema>,
+        visibility: &ChatVisibility,
+        access_policy: &AccessPolicy,
+    ) -> usize {
+        18 + RateLimit::INIT_SPACE
+            + text.len()
+            + content_ref.map_or(0, ContentRef::space)
+            + response_schema.map_or(0, ResponseSchema::space)
+            + visibility.space()
+            + access_policy.space()
+    }
+
+    /// Grows a chat context created with the legacy layout into a ready, private chat without
+    /// restrictions or a rate limit of its own. Current chat contexts are left as they are.
+    pub fn grow<'info>(
+        chat_context: &AccountInfo<'info>,
+        payer: &Signer<'info>,
+        system_program: &Program<'info, System>,
+    ) -> Result<()> {
+        let legacy = {
+            let data = chat_context.try_borrow_data()?;
+            if ChatContext::try_deserialize(&mut data.as_ref()).is_ok() {
+                return Ok(());
+            }
+            require!(
+                data.starts_with(ChatContext::DISCRIMINATOR),
+                ErrorCode::AccountDiscriminatorMismatch
+            );
+            LegacyChatContext::deserialize(&mut &data[8..])?
+        };
+
+        let chat = ChatContext {
+            text: legacy.text,
+            seed: legacy.seed,
+            bump: legacy.bump,
+            // there was no chunked upload yet, every chat was usable right away
+            is_ready: true,
+            content_ref: None,
+            response_schema: None,
+            visibility: ChatVisibility::Private,
+            access_policy: AccessPolicy::default(),
+            rate_limit: None,
+        };
+        let space = ChatContext::space(
+            &chat.text,
+            None,
+            None,
+            &chat.visibility,
+            &chat.access_policy,
+        );
+
+        let rent = Rent::get()?
+            .minimum_balance(space)
+            .saturating_sub(chat_context.lamports());
+        if rent > 0 {
+            let cpi_context = CpiContext::new(
+                system_program.to_account_info(),
+                Transfer {
+                    from: payer.to_account_info(),
+                    to: chat_context.clone(),
+                },
+            );
+            transfer(cpi_context, rent)?;
+        }
+        chat_context.resize(space)?;
+        chat.try_serialize(&mut chat_context.try_borrow_mut_data()?.as_mut())?;
+        Ok(())
+    }
+
+    /// Whether `user` is the owner the chat context at `address` was derived from.
+    pub fn is_owner(&self, address: &Pubkey, user: &Pubkey) -> bool {
+        Pubkey::create_program_address(
+            &[
+                b"chat_context",
+                user.as_ref(),
+                self.seed.to_le_bytes().as_ref(),
+                &[self.bump],
+            ],
+            &crate::ID,
+        )
+        .is_ok_and(|owner_address| owner_address == *address)
+    }
+
+    /// Whether `user` may send inferences to the chat context at `address`.
+    pub fn is_permitted(&self, address: &Pubkey, user: &Pubkey) -> bool {
+        match &self.visibility {
+            ChatVisibility::Public => true,
+            ChatVisibility::Private => self.is_owner(address, user),
+            ChatVisibility::Allowlist(users) => {
+                users.contains(user) || self.is_owner(address, user)
+            }
+        }
+    }
 }
@@ -1,7 +1,47 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+use crate::RateLimit;
 
 #[account]
 #[derive(InitSpace)]
 pub struct Config {
     pub bump: u8,
+    /// Applies to chat contexts without a rate limit of their own.
+    pub default_rate_limit: Option<RateLimit>,
+    /// Stops new inferences, batches, schedule runs and the chats, workflows and uploads they need.
+    pub requests_paused: bool,
+    /// Stops every instruction that would call back.
+    pub callbacks_paused: bool,
+}
+
+impl Config {
+    /// Grows a config created before its newer fields existed, their zeroed bytes read as
+    /// the defaults. `migrate_config` and the admin updates call this before loading the config.
+    pub fn grow<'info>(
+        config: &AccountInfo<'info>,
+        payer: &Signer<'info>,
+        system_program: &Program<'info, System>,
+    ) -> Result<()> {
+        let space = 8 + Config::INIT_SPACE;
+        if config.data_len() >= space {
+            return Ok(());
+        }
+
+        let rent = Rent::get()?
+            .minimum_balance(space)
+            .saturating_sub(config.lamports());
+        if rent > 0 {
+            let cpi_context = CpiContext::new(
+                system_program.to_account_info(),
+                Transfer {
+                    from: payer.to_account_info(),
+                    to: config.clone(),
+                },
+            );
+            transfer(cpi_context, rent)?;
+        }
+        config.resize(space)?;
+        Ok(())
+    }
 }
@@ -0,0 +1,16 @@
+use anchor_lang::prelude::*;
+
+/// Points at prompt content kept off-chain. The oracle fetches `uri` and only
+/// uses the content when it hashes to `sha256`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ContentRef {
+    pub uri: String,
+    pub sha256: [u8; 32],
+}
+
+impl ContentRef {
+    // 36 = 4 + 32
+    pub fn space(&self) -> usize {
+        36 + self.uri.len()
+    }
+}
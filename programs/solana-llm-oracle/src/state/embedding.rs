@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+
+use crate::MAX_EMBEDDING_DIMENSIONS;
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Quantization {
+    I8,
+    /// IEEE 754 half precision, carried as the `u16` bits.
+    F16,
+}
+
+impl Quantization {
+    pub fn size(&self) -> usize {
+        match self {
+            Quantization::I8 => 1,
+            Quantization::F16 => 2,
+        }
+    }
+}
+
+/// Turns the inference into an embedding request: the oracle embeds the inference text
+/// instead of completing the prompt, the callback gets the vector as a borsh `Vec<i8>`,
+/// or a `Vec<u16>` of f16 bits.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EmbeddingConfig {
+    pub quantization: Quantization,
+    /// Longer vectors are cut down to this many dimensions.
+    pub max_dimensions: u16,
+}
+
+impl EmbeddingConfig {
+    pub fn is_valid(&self) -> bool {
+        self.max_dimensions > 0 && self.max_dimensions <= MAX_EMBEDDING_DIMENSIONS
+    }
+
+    /// Length of the encoded vector at full dimensions.
+    pub fn max_len(&self) -> usize {
+        4 + self.max_dimensions as usize * self.quantization.size()
+    }
+
+    /// Whether `value` is a borsh encoded vector within the dimension cap.
+    pub fn validate(&self, value: &[u8]) -> bool {
+        let Some((len, values)) = value.split_first_chunk::<4>() else {
+            return false;
+        };
+        let dimensions = u32::from_le_bytes(*len) as usize;
+        dimensions > 0
+            && dimensions <= self.max_dimensions as usize
+            && values.len() == dimensions * self.quantization.size()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_checks_length_prefix_and_cap() {
+        let embedding = EmbeddingConfig {
+            quantization: Quantization::F16,
+            max_dimensions: 2,
+        };
+        assert!(embedding.validate(&[2, 0, 0, 0, 1, 2, 3, 4]));
+        assert!(embedding.validate(&[1, 0, 0, 0, 1, 2]));
+        assert!(!embedding.validate(&[2, 0, 0, 0, 1, 2]));
+        assert!(!embedding.validate(&[3, 0, 0, 0, 1, 2, 3, 4, 5, 6]));
+        assert!(!embedding.validate(&[0, 0, 0, 0]));
+        assert!(!embedding.validate(&[1, 0]));
+        assert_eq!(embedding.max_len(), 8);
+    }
+
+    #[test]
+    fn is_valid_caps_dimensions() {
+        let embedding = |max_dimensions| EmbeddingConfig {
+            quantization: Quantization::I8,
+            max_dimensions,
+        };
+        assert!(embedding(MAX_EMBEDDING_DIMENSIONS).is_valid());
+        assert!(!embedding(MAX_EMBEDDING_DIMENSIONS + 1).is_valid());
+        assert!(!embedding(0).is_valid());
+    }
+}
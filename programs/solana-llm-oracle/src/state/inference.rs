@@ -1,15 +1,46 @@
 use anchor_lang::prelude::*;
 
+use crate::error::OracleError;
+use crate::{
+    Attestation, CallbackBudget, CallbackFormat, ContentRef, EmbeddingConfig, OutputType,
+    QuorumConfig, ResponseSchema, REQUESTER_SEED,
+};
+
 #[account]
 #[derive(Default, Debug)]
 pub struct Inference {
     pub chat_context: Pubkey,
     pub user: Pubkey,
+    pub nonce: u64,
     pub text: String,
+    pub content_ref: Option<ContentRef>,
     pub callback_program_id: Pubkey,
     pub callback_discriminator: [u8; 8],
     pub callback_account_metas: Vec<AccountMeta>,
+    pub callback_format: CallbackFormat,
+    /// `None` leaves the callback transaction's compute budget to the oracle.
+    pub callback_budget: Option<CallbackBudget>,
+    /// Lamports held on top of rent to pay for the callback transaction, released to the oracle with the callback.
+    pub escrowed_fee: u64,
+    /// Program that requested the inference through its `REQUESTER_SEED` pda, see `verify_requester`.
+    pub requester_program: Option<Pubkey>,
+    pub lookup_tables: Vec<Pubkey>,
+    pub max_response_len: u32,
+    pub output_type: Option<OutputType>,
+    /// Overrides the chat context's schema.
+    pub response_schema: Option<ResponseSchema>,
+    pub embedding: Option<EmbeddingConfig>,
+    /// Workflow whose steps the oracle runs before answering, the inference text is the `{input}`.
+    pub workflow: Option<Pubkey>,
+    pub quorum: Option<QuorumConfig>,
+    /// Slot the request became ready for the oracle, quorum windows count from here.
+    pub requested_slot: u64,
+    /// Slots a proposed response can be challenged for before the callback fires, 0 calls back right away.
+    pub dispute_slots: u64,
+    pub is_ready: bool,
     pub is_processed: bool,
+    pub is_failed: bool,
+    pub attestation: Option<Attestation>,
 }
 
 impl Inference {
@@ -17,12 +48,50 @@ impl Inference {
         b"inference"
     }
 
-    // 121 = 8 + 32 + 32 + 32 + 8 + 1 + 4 + 4
-    pub fn space(text: &String, account_metas_len: usize) -> usize {
-        121 + text.as_bytes().len() + account_metas_len * AccountMeta::size()
+    // 237 = 8 + 32 + 32 + 8 + 32 + 8 + 1 + 1 + 8 + 33 + 33 + 1 + 1 + 1 + 1 + 1 + 8 + 8 + 1 + 1 + 1 + 1 + 4 + 4 + 4 + 4
+    // room for the attestation is reserved up front, the oracle fills it in on callback
+    pub fn space(&self) -> usize {
+        237 + Attestation::INIT_SPACE
+            + CallbackBudget::INIT_SPACE
+            + EmbeddingConfig::INIT_SPACE
+            + QuorumConfig::INIT_SPACE
+            + self.text.len()
+            + self.content_ref.as_ref().map_or(0, ContentRef::space)
+            + self.output_type.as_ref().map_or(0, OutputType::space)
+            + self
+                .response_schema
+                .as_ref()
+                .map_or(0, ResponseSchema::space)
+            + self.callback_account_metas.len() * AccountMeta::size()
+            + self.lookup_tables.len() * 32
+    }
+
+    /// The pda `program_id` signs with to be recorded as `requester_program`.
+    pub fn requester_address(program_id: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[REQUESTER_SEED], program_id).0
     }
 }
 
+/// For callback programs: checks that the oracle is calling, `config` signing, and that
+/// the inference being answered was requested by `program_id` itself. Needs the inference
+/// account, so the inference should use `CallbackFormat::WithMetadata`.
+pub fn verify_requester(
+    config: &AccountInfo,
+    inference: &Account<Inference>,
+    program_id: &Pubkey,
+) -> Result<()> {
+    let (config_address, _) = Pubkey::find_program_address(&[b"config"], &crate::ID);
+    require!(
+        config.is_signer && config.key() == config_address,
+        OracleError::RequesterMismatch
+    );
+    require!(
+        inference.requester_program == Some(*program_id),
+        OracleError::RequesterMismatch
+    );
+    Ok(())
+}
+
 #[derive(InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Debug)]
 pub struct AccountMeta {
     pub pubkey: Pubkey,
@@ -35,3 +104,36 @@ impl AccountMeta {
         8 + AccountMeta::INIT_SPACE
     }
 }
+
+/// Optional knobs for `create_llm_inference`, `None` keeps the defaults.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct InferenceOptions {
+    /// Address lookup tables the oracle should use when building the callback transaction.
+    pub lookup_tables: Vec<Pubkey>,
+    /// Pass the inference account and request metadata along with the response.
+    pub callback_format: CallbackFormat,
+    /// Compute units the callback transaction should get, the fee it costs is escrowed on the inference.
+    pub compute_unit_limit: Option<u32>,
+    /// Highest compute unit price the oracle may pay for the callback, in micro-lamports.
+    pub priority_fee_cap: Option<u64>,
+    /// Program to record as the requester, the `requester` account has to be its `REQUESTER_SEED` pda and sign.
+    pub requester_program: Option<Pubkey>,
+    /// Longest response in bytes the callback accepts, capped at `MAX_RESPONSE_LEN`.
+    pub max_response_len: Option<u32>,
+    /// Leave the inference unfinalized so the prompt can be extended with `append_inference_text`.
+    pub chunked_upload: bool,
+    /// Off-chain prompt content the oracle uses in place of the inline text.
+    pub content_ref: Option<ContentRef>,
+    /// Have the oracle coerce the answer into a typed value, the callback gets it borsh encoded instead of a `String`.
+    pub output_type: Option<OutputType>,
+    /// JSON schema the answer has to follow, in place of the chat context's.
+    pub response_schema: Option<ResponseSchema>,
+    /// Ask for an embedding of the inference text instead of a completion.
+    pub embedding: Option<EmbeddingConfig>,
+    /// Run the inference through a `Workflow`, only the last step's response is called back.
+    pub workflow: Option<Pubkey>,
+    /// Have several registered oracles answer and only call back once enough of them agree.
+    pub quorum: Option<QuorumConfig>,
+    /// Optimistic mode: store the response first and only call back once nobody challenged it for this many slots.
+    pub dispute_slots: Option<u64>,
+}
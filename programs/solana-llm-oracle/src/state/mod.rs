@@ -1,7 +1,37 @@
+pub mod attestation;
+pub mod batch;
+pub mod callback_budget;
+pub mod callback_format;
+pub mod challenge;
 pub mod chat_context;
 pub mod config;
+pub mod content_ref;
+pub mod embedding;
 pub mod inference;
+pub mod oracle_node;
+pub mod output_type;
+pub mod quorum;
+pub mod rate_limit;
+pub mod response_buffer;
+pub mod response_schema;
+pub mod schedule;
+pub mod workflow;
 
+pub use attestation::*;
+pub use batch::*;
+pub use callback_budget::*;
+pub use callback_format::*;
+pub use challenge::*;
 pub use chat_context::*;
 pub use config::*;
+pub use content_ref::*;
+pub use embedding::*;
 pub use inference::*;
+pub use oracle_node::*;
+pub use output_type::*;
+pub use quorum::*;
+pub use rate_limit::*;
+pub use response_buffer::*;
+pub use response_schema::*;
+pub use schedule::*;
+pub use workflow::*;
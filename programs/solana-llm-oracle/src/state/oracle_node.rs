@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+
+use crate::{MAX_ATTESTATION_SLASH, MAX_MISSED_REVEAL_SLASH};
+
+/// An oracle the admin registered to take part in quorum inferences. The
+/// account doubles as the oracle's stake vault, staked lamports sit on top of its rent.
+#[account]
+#[derive(InitSpace)]
+pub struct OracleNode {
+    pub identity: Pubkey,
+    /// Lamports backing the oracle's submissions, slashable.
+    pub stake: u64,
+    /// Lamports on their way out, still slashable until `unbonding_slot`.
+    pub unbonding: u64,
+    pub unbonding_slot: u64,
+    pub bump: u8,
+}
+
+impl OracleNode {
+    pub fn seed() -> &'static [u8] {
+        b"oracle"
+    }
+}
+
+/// Provable misbehavior `slash_oracle` accepts.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub enum Offense {
+    /// The oracle signed an attestation for a different response than the one it revealed.
+    /// The signature is checked through an ed25519 instruction right before `slash_oracle`.
+    ContradictingAttestation {
+        response_hash: [u8; 32],
+        model_id: String,
+    },
+    /// The oracle committed to a response and never revealed it before the deadline.
+    MissedReveal,
+}
+
+impl Offense {
+    /// Most lamports a single offense of this kind can be slashed for.
+    pub fn max_slash(&self) -> u64 {
+        match self {
+            Offense::ContradictingAttestation { .. } => MAX_ATTESTATION_SLASH,
+            Offense::MissedReveal => MAX_MISSED_REVEAL_SLASH,
+        }
+    }
+}
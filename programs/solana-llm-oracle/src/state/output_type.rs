@@ -0,0 +1,171 @@
+use anchor_lang::prelude::*;
+
+use crate::{MAX_CONFIDENCE_BPS, MAX_OUTPUT_LABELS, MAX_OUTPUT_LABEL_LEN};
+
+/// What the callback receives in place of the raw text. The oracle coerces the model
+/// output into the type, the callback data carries it borsh encoded.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum OutputType {
+    U8 {
+        min: u8,
+        max: u8,
+    },
+    U64 {
+        min: u64,
+        max: u64,
+    },
+    I64 {
+        min: i64,
+        max: i64,
+    },
+    Bool,
+    /// Encoded as the `u8` index of the label.
+    Label {
+        labels: Vec<String>,
+    },
+    Pubkey,
+    /// Encoded as the `u8` index of the label followed by the model's `u16` confidence
+    /// in basis points.
+    Classification {
+        labels: Vec<String>,
+    },
+}
+
+impl OutputType {
+    pub fn space(&self) -> usize {
+        1 + match self {
+            OutputType::U8 { .. } => 2,
+            OutputType::U64 { .. } | OutputType::I64 { .. } => 16,
+            OutputType::Bool | OutputType::Pubkey => 0,
+            OutputType::Label { labels } | OutputType::Classification { labels } => {
+                4 + labels.iter().map(|label| 4 + label.len()).sum::<usize>()
+            }
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        match self {
+            OutputType::U8 { min, max } => min <= max,
+            OutputType::U64 { min, max } => min <= max,
+            OutputType::I64 { min, max } => min <= max,
+            OutputType::Bool | OutputType::Pubkey => true,
+            OutputType::Label { labels } | OutputType::Classification { labels } => {
+                !labels.is_empty()
+                    && labels.len() <= MAX_OUTPUT_LABELS
+                    && labels
+                        .iter()
+                        .all(|label| label.len() <= MAX_OUTPUT_LABEL_LEN)
+            }
+        }
+    }
+
+    /// Whether `value` is a borsh encoded value of this type, within its range.
+    pub fn validate(&self, value: &[u8]) -> bool {
+        match self {
+            OutputType::U8 { min, max } => {
+                matches!(value, [byte] if (*min..=*max).contains(byte))
+            }
+            OutputType::U64 { min, max } => <[u8; 8]>::try_from(value)
+                .is_ok_and(|bytes| (*min..=*max).contains(&u64::from_le_bytes(bytes))),
+            OutputType::I64 { min, max } => <[u8; 8]>::try_from(value)
+                .is_ok_and(|bytes| (*min..=*max).contains(&i64::from_le_bytes(bytes))),
+            OutputType::Bool => matches!(value, [0] | [1]),
+            OutputType::Label { labels } => {
+                matches!(value, [index] if (*index as usize) < labels.len())
+            }
+            OutputType::Pubkey => value.len() == 32,
+            OutputType::Classification { labels } => match value {
+                [index, bps @ ..] => {
+                    (*index as usize) < labels.len()
+                        && <[u8; 2]>::try_from(bps)
+                            .is_ok_and(|bps| u16::from_le_bytes(bps) <= MAX_CONFIDENCE_BPS)
+                }
+                _ => false,
+            },
+        }
+    }
+}
+
+/// The response as an integer, for median agreement. Untyped responses are parsed as text.
+pub fn numeric_value(output_type: Option<&OutputType>, response: &[u8]) -> Option<i64> {
+    match output_type {
+        None => std::str::from_utf8(response).ok()?.trim().parse().ok(),
+        Some(OutputType::U8 { .. }) => response.first().map(|byte| *byte as i64),
+        Some(OutputType::U64 { .. }) => <[u8; 8]>::try_from(response)
+            .ok()
+            .and_then(|bytes| i64::try_from(u64::from_le_bytes(bytes)).ok()),
+        Some(OutputType::I64 { .. }) => <[u8; 8]>::try_from(response).ok().map(i64::from_le_bytes),
+        Some(_) => None,
+    }
+}
+
+/// Encodes an agreed integer the way `numeric_value` read it.
+pub fn encode_numeric(output_type: Option<&OutputType>, value: i64) -> Vec<u8> {
+    match output_type {
+        Some(OutputType::U8 { .. }) => vec![value as u8],
+        Some(OutputType::U64 { .. }) => (value as u64).to_le_bytes().to_vec(),
+        Some(OutputType::I64 { .. }) => value.to_le_bytes().to_vec(),
+        _ => value.to_string().into_bytes(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels() -> Vec<String> {
+        vec!["yes".to_string(), "no".to_string()]
+    }
+
+    #[test]
+    fn validate_checks_range_and_layout() {
+        let u8_type = OutputType::U8 { min: 1, max: 5 };
+        assert!(u8_type.validate(&[5]));
+        assert!(!u8_type.validate(&[6]));
+        assert!(!u8_type.validate(&[1, 0]));
+
+        let i64_type = OutputType::I64 { min: -10, max: 10 };
+        assert!(i64_type.validate(&(-10i64).to_le_bytes()));
+        assert!(!i64_type.validate(&11i64.to_le_bytes()));
+        assert!(!i64_type.validate(&[0; 4]));
+
+        assert!(OutputType::Bool.validate(&[1]));
+        assert!(!OutputType::Bool.validate(&[2]));
+        assert!(OutputType::Pubkey.validate(&[0; 32]));
+        assert!(!OutputType::Pubkey.validate(&[0; 31]));
+
+        let label = OutputType::Label { labels: labels() };
+        assert!(label.validate(&[1]));
+        assert!(!label.validate(&[2]));
+    }
+
+    #[test]
+    fn validate_caps_classification_confidence() {
+        let classification = OutputType::Classification { labels: labels() };
+        let with_bps = |index: u8, bps: u16| [&[index], bps.to_le_bytes().as_slice()].concat();
+
+        assert!(classification.validate(&with_bps(0, MAX_CONFIDENCE_BPS)));
+        assert!(!classification.validate(&with_bps(0, MAX_CONFIDENCE_BPS + 1)));
+        assert!(!classification.validate(&with_bps(2, 0)));
+        assert!(!classification.validate(&[0]));
+    }
+
+    #[test]
+    fn encode_numeric_round_trips() {
+        let types = [
+            None,
+            Some(OutputType::U8 { min: 0, max: 255 }),
+            Some(OutputType::U64 { min: 0, max: 100 }),
+            Some(OutputType::I64 { min: -5, max: 5 }),
+        ];
+        for output_type in &types {
+            let encoded = encode_numeric(output_type.as_ref(), 42);
+            assert_eq!(numeric_value(output_type.as_ref(), &encoded), Some(42));
+        }
+        assert_eq!(encode_numeric(None, -7), b"-7");
+        assert_eq!(
+            encode_numeric(Some(&OutputType::I64 { min: -10, max: 0 }), -7),
+            (-7i64).to_le_bytes()
+        );
+    }
+}
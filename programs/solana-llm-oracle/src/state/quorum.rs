@@ -0,0 +1,220 @@
+use anchor_lang::{prelude::*, solana_program::hash::hashv};
+
+use crate::MAX_QUORUM_SIZE;
+
+/// Opt-in multi-oracle mode: up to `size` registered oracles submit, and the
+/// callback fires once `threshold` of them agree.
+#[derive(InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct QuorumConfig {
+    pub size: u8,
+    pub threshold: u8,
+    pub mode: AgreementMode,
+    /// Median mode only, how far from the median a value may be and still agree.
+    pub tolerance: u64,
+    /// Have oracles commit to a hash first, so nobody can copy an answer before revealing their own.
+    pub commit_reveal: Option<CommitReveal>,
+}
+
+#[derive(InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AgreementMode {
+    /// Responses agree when they are byte for byte the same.
+    Exact,
+    /// Responses are parsed as integers and the callback gets their median.
+    Median,
+}
+
+/// Slot windows of a commit-reveal quorum, counted from `Inference.requested_slot`.
+#[derive(InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct CommitReveal {
+    pub commit_slots: u64,
+    pub reveal_slots: u64,
+}
+
+impl QuorumConfig {
+    // a strict majority, so two disjoint groups can never both reach the threshold
+    pub fn is_valid(&self) -> bool {
+        self.size as usize <= MAX_QUORUM_SIZE
+            && self.threshold <= self.size
+            && self.threshold as usize * 2 > self.size as usize
+            && self
+                .commit_reveal
+                .is_none_or(|windows| windows.commit_slots > 0 && windows.reveal_slots > 0)
+    }
+}
+
+#[derive(InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct Submission {
+    pub oracle: Pubkey,
+    /// `Submissions::commitment` of the response, zeroed without commit-reveal.
+    pub commitment: [u8; 32],
+    /// Only revealed responses count towards agreement.
+    pub revealed: bool,
+    pub response_hash: [u8; 32],
+    /// The response parsed as an integer, `None` when it isn't one.
+    pub value: Option<i64>,
+    /// Set once the oracle was slashed for this entry, an offense only costs it once.
+    pub slashed: bool,
+}
+
+/// Collects the oracles' responses to one inference request.
+#[account]
+#[derive(InitSpace)]
+pub struct Submissions {
+    pub inference: Pubkey,
+    pub nonce: u64,
+    /// Last slot commitments are accepted in, reveals start right after.
+    pub commit_deadline: u64,
+    /// Last slot reveals are accepted in.
+    pub reveal_deadline: u64,
+    #[max_len(MAX_QUORUM_SIZE)]
+    pub entries: Vec<Submission>,
+    #[max_len(MAX_QUORUM_SIZE)]
+    pub agreed: Vec<Pubkey>,
+    #[max_len(MAX_QUORUM_SIZE)]
+    pub dissented: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+impl Submissions {
+    pub fn seed() -> &'static [u8] {
+        b"submissions"
+    }
+
+    /// What `oracle` commits to before revealing `response` to request `nonce` of `inference`.
+    /// Binding the oracle and request keeps anyone from replaying another oracle's commitment.
+    pub fn commitment(
+        oracle: &Pubkey,
+        inference: &Pubkey,
+        nonce: u64,
+        response: &[u8],
+        salt: &[u8; 32],
+    ) -> [u8; 32] {
+        hashv(&[
+            oracle.as_ref(),
+            inference.as_ref(),
+            &nonce.to_le_bytes(),
+            response,
+            salt,
+        ])
+        .to_bytes()
+    }
+
+    fn revealed(&self) -> impl Iterator<Item = &Submission> {
+        self.entries.iter().filter(|entry| entry.revealed)
+    }
+
+    /// Oracles whose response matches `response_hash` byte for byte.
+    pub fn exact_agreement(&self, response_hash: &[u8; 32]) -> Vec<Pubkey> {
+        self.revealed()
+            .filter(|entry| entry.response_hash == *response_hash)
+            .map(|entry| entry.oracle)
+            .collect()
+    }
+
+    /// The revealed response hash at least `threshold` oracles agree on, if there is one.
+    pub fn exact_majority(&self, threshold: u8) -> Option<[u8; 32]> {
+        self.revealed()
+            .map(|entry| entry.response_hash)
+            .find(|response_hash| self.exact_agreement(response_hash).len() >= threshold as usize)
+    }
+
+    /// The (lower) median of the numeric responses and the oracles within `tolerance` of it.
+    pub fn median_agreement(&self, tolerance: u64) -> Option<(i64, Vec<Pubkey>)> {
+        let mut values: Vec<i64> = self.revealed().filter_map(|entry| entry.value).collect();
+        if values.is_empty() {
+            return None;
+        }
+        values.sort_unstable();
+        let median = values[(values.len() - 1) / 2];
+
+        let agreed = self
+            .revealed()
+            .filter(|entry| {
+                entry
+                    .value
+                    .is_some_and(|value| value.abs_diff(median) <= tolerance)
+            })
+            .map(|entry| entry.oracle)
+            .collect();
+        Some((median, agreed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn submission(revealed: bool, response_hash: u8, value: Option<i64>) -> Submission {
+        Submission {
+            oracle: Pubkey::new_unique(),
+            commitment: [0; 32],
+            revealed,
+            response_hash: [response_hash; 32],
+            value,
+            slashed: false,
+        }
+    }
+
+    fn submissions(entries: Vec<Submission>) -> Submissions {
+        Submissions {
+            inference: Pubkey::new_unique(),
+            nonce: 0,
+            commit_deadline: 0,
+            reveal_deadline: 0,
+            entries,
+            agreed: Vec::new(),
+            dissented: Vec::new(),
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn exact_agreement_counts_matching_reveals() {
+        let submissions = submissions(vec![
+            submission(true, 1, None),
+            submission(true, 1, None),
+            submission(true, 2, None),
+            submission(false, 1, None),
+        ]);
+
+        let agreed = submissions.exact_agreement(&[1; 32]);
+        assert_eq!(
+            agreed,
+            vec![submissions.entries[0].oracle, submissions.entries[1].oracle]
+        );
+        assert_eq!(submissions.exact_majority(2), Some([1; 32]));
+        assert_eq!(submissions.exact_majority(3), None);
+    }
+
+    #[test]
+    fn median_agreement_takes_the_lower_median() {
+        let submissions = submissions(vec![
+            submission(true, 0, Some(10)),
+            submission(true, 0, Some(12)),
+            submission(true, 0, Some(30)),
+            submission(true, 0, Some(11)),
+            submission(true, 0, None),
+            submission(false, 0, Some(11)),
+        ]);
+
+        let (median, agreed) = submissions.median_agreement(1).unwrap();
+        assert_eq!(median, 11);
+        assert_eq!(
+            agreed,
+            vec![
+                submissions.entries[0].oracle,
+                submissions.entries[1].oracle,
+                submissions.entries[3].oracle,
+            ]
+        );
+    }
+
+    #[test]
+    fn median_agreement_needs_a_numeric_reveal() {
+        let submissions = submissions(vec![
+            submission(true, 0, None),
+            submission(false, 0, Some(1)),
+        ]);
+        assert!(submissions.median_agreement(0).is_none());
+    }
+}
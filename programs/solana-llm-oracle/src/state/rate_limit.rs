@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+
+use crate::error::OracleError;
+
+/// At most `max_requests` inferences per user within a window of `window_slots` slots.
+#[derive(InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RateLimit {
+    pub max_requests: u32,
+    pub window_slots: u64,
+}
+
+impl RateLimit {
+    pub fn is_valid(&self) -> bool {
+        self.max_requests > 0 && self.window_slots > 0
+    }
+}
+
+/// A user's requests to one chat context in the current rate limit window.
+#[account]
+#[derive(InitSpace)]
+pub struct Usage {
+    pub window_start: u64,
+    pub requests: u32,
+    pub bump: u8,
+}
+
+impl Usage {
+    pub fn seed() -> &'static [u8] {
+        b"usage"
+    }
+
+    /// Counts a request against `rate_limit`, starting a new window once the last one is over.
+    pub fn record(&mut self, slot: u64, rate_limit: &RateLimit) -> Result<()> {
+        if slot >= self.window_start.saturating_add(rate_limit.window_slots) {
+            self.window_start = slot;
+            self.requests = 0;
+        }
+        require!(
+            self.requests < rate_limit.max_requests,
+            OracleError::RateLimited
+        );
+        self.requests += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE_LIMIT: RateLimit = RateLimit {
+        max_requests: 2,
+        window_slots: 10,
+    };
+
+    #[test]
+    fn record_limits_requests_per_window() {
+        let mut usage = Usage {
+            window_start: 0,
+            requests: 0,
+            bump: 0,
+        };
+        usage.record(100, &RATE_LIMIT).unwrap();
+        usage.record(105, &RATE_LIMIT).unwrap();
+        assert!(usage.record(109, &RATE_LIMIT).is_err());
+        assert_eq!(usage.requests, 2);
+
+        // the window started at slot 100, so slot 110 opens the next one
+        usage.record(110, &RATE_LIMIT).unwrap();
+        assert_eq!(usage.window_start, 110);
+        assert_eq!(usage.requests, 1);
+    }
+}
@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+
+/// Staging area for responses too large for a single callback transaction.
+/// The oracle writes chunks into `data`, the delivery ixn checks them against
+/// `response_hash` and closes the buffer.
+/// Optimistic inferences keep the proposed response here until their dispute window passes.
+#[account]
+pub struct ResponseBuffer {
+    pub inference: Pubkey,
+    /// Request the response answers, buffers of an earlier nonce are stale.
+    pub nonce: u64,
+    pub response_hash: [u8; 32],
+    pub data: Vec<u8>,
+    /// Last slot the proposed response can be challenged in, 0 until proposed.
+    pub dispute_deadline: u64,
+    pub is_challenged: bool,
+    pub bump: u8,
+}
+
+impl ResponseBuffer {
+    pub fn seed() -> &'static [u8] {
+        b"response"
+    }
+
+    // 94 = 8 + 32 + 8 + 32 + 4 + 8 + 1 + 1
+    pub fn space(total_len: u32) -> usize {
+        94 + total_len as usize
+    }
+}
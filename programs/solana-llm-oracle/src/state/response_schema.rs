@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+
+use crate::ContentRef;
+
+/// JSON schema the oracle's answer has to follow, passed to the model as its structured output format.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub enum ResponseSchema {
+    Inline {
+        schema: String,
+    },
+    /// Off-chain schema, checked against its sha256 like any other `ContentRef`.
+    Ref {
+        content_ref: ContentRef,
+    },
+}
+
+impl ResponseSchema {
+    pub fn space(&self) -> usize {
+        1 + match self {
+            ResponseSchema::Inline { schema } => 4 + schema.len(),
+            ResponseSchema::Ref { content_ref } => content_ref.space(),
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use anchor_lang::prelude::*;
+
+use crate::state::AccountMeta;
+use crate::{Inference, SCHEDULE_RUN_FEE};
+
+/// How often a schedule runs, counted from its previous run.
+#[derive(InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScheduleInterval {
+    Slots(u64),
+    Seconds(i64),
+}
+
+impl ScheduleInterval {
+    pub fn is_valid(&self) -> bool {
+        match self {
+            ScheduleInterval::Slots(slots) => *slots > 0,
+            ScheduleInterval::Seconds(seconds) => *seconds > 0,
+        }
+    }
+}
+
+/// When and how often a schedule runs, and what it starts out with to pay for the runs.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct ScheduleConfig {
+    pub interval: ScheduleInterval,
+    pub max_runs: u32,
+    /// Lamports moved into the schedule's balance on creation.
+    pub deposit: u64,
+}
+
+impl ScheduleConfig {
+    pub fn is_valid(&self) -> bool {
+        self.interval.is_valid() && self.max_runs > 0
+    }
+}
+
+/// A prompt the oracle sends to a chat context on its own every interval, paid for
+/// out of a prepaid balance. Each run refreshes the schedule's own inference, keyed
+/// by the schedule in place of a user.
+#[account]
+pub struct Schedule {
+    pub chat_context: Pubkey,
+    pub user: Pubkey,
+    pub schedule_id: u64,
+    pub prompt: String,
+    pub interval: ScheduleInterval,
+    pub callback_program_id: Pubkey,
+    pub callback_discriminator: [u8; 8],
+    pub callback_account_metas: Vec<AccountMeta>,
+    /// Lamports prepaid for runs on top of the account's rent, each run takes `SCHEDULE_RUN_FEE`.
+    pub balance: u64,
+    pub max_runs: u32,
+    pub runs: u32,
+    pub last_run_slot: u64,
+    pub last_run_timestamp: i64,
+    pub bump: u8,
+}
+
+impl Schedule {
+    pub fn seed() -> &'static [u8] {
+        b"schedule"
+    }
+
+    // 161 = 8 + 32 + 32 + 8 + 4 + 32 + 8 + 4 + 8 + 4 + 4 + 8 + 8 + 1
+    pub fn space(prompt: &str, callback_account_metas: usize) -> usize {
+        161 + ScheduleInterval::INIT_SPACE
+            + prompt.len()
+            + callback_account_metas * AccountMeta::size()
+    }
+
+    /// Space of the schedule's inference, the prompt and callback never change between runs.
+    pub fn inference_space(prompt: &str, callback_account_metas: &[AccountMeta]) -> usize {
+        Inference {
+            text: prompt.to_string(),
+            callback_account_metas: callback_account_metas.to_vec(),
+            ..Default::default()
+        }
+        .space()
+    }
+
+    pub fn inference_address(schedule: &Pubkey, chat_context: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[Inference::seed(), schedule.as_ref(), chat_context.as_ref()],
+            &crate::ID,
+        )
+        .0
+    }
+
+    pub fn is_funded(&self) -> bool {
+        self.runs < self.max_runs && self.balance >= SCHEDULE_RUN_FEE
+    }
+
+    /// Whether a full interval has passed since the last run, the first run is due right away.
+    pub fn is_due(&self, clock: &Clock) -> bool {
+        if self.runs == 0 {
+            return true;
+        }
+        match self.interval {
+            ScheduleInterval::Slots(slots) => {
+                clock.slot.saturating_sub(self.last_run_slot) >= slots
+            }
+            ScheduleInterval::Seconds(seconds) => {
+                clock.unix_timestamp.saturating_sub(self.last_run_timestamp) >= seconds
+            }
+        }
+    }
+}
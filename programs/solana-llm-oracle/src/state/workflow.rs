@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+use crate::MAX_WORKFLOW_STEPS;
+
+/// An ordered list of prompt templates the oracle walks through for one inference.
+/// `{input}` in a template is replaced with the inference text and `{previous}` with
+/// the previous step's response, only the last step's response reaches the callback.
+#[account]
+pub struct Workflow {
+    pub user: Pubkey,
+    pub steps: Vec<String>,
+    pub seed: u8,
+    pub bump: u8,
+}
+
+impl Workflow {
+    pub fn seed() -> &'static [u8] {
+        b"workflow"
+    }
+
+    // 46 = 8 + 32 + 4 + 1 + 1
+    pub fn space(steps: &[String]) -> usize {
+        46 + steps.iter().map(|step| 4 + step.len()).sum::<usize>()
+    }
+}
+
+/// The hashes of a workflow inference's intermediate responses, the texts themselves
+/// are in the `WorkflowStepRecorded` events.
+#[account]
+#[derive(InitSpace)]
+pub struct WorkflowRun {
+    pub inference: Pubkey,
+    pub nonce: u64,
+    pub workflow: Pubkey,
+    #[max_len(MAX_WORKFLOW_STEPS)]
+    pub step_hashes: Vec<[u8; 32]>,
+    pub bump: u8,
+}
+
+impl WorkflowRun {
+    pub fn seed() -> &'static [u8] {
+        b"workflow_run"
+    }
+}
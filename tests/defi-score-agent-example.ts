@@ -16,12 +16,13 @@ describe("defi-score-agent-example", () => {
     .DefiScoreAgentExample as Program<DefiScoreAgentExample>;
 
   let seed = 0;
+  const [agent] = PublicKey.findProgramAddressSync(
+    [Buffer.from("agent")],
+    program.programId
+  );
+  // the agent pda owns the chat
   const [chatContext] = PublicKey.findProgramAddressSync(
-    [
-      Buffer.from("chat_context"),
-      payer.publicKey.toBuffer(),
-      Buffer.from([seed]),
-    ],
+    [Buffer.from("chat_context"), agent.toBuffer(), Buffer.from([seed])],
     llmProgramAddress
   );
 
@@ -42,11 +43,21 @@ describe("defi-score-agent-example", () => {
     llmProgramAddress
   );
 
+  const [usage] = PublicKey.findProgramAddressSync(
+    [Buffer.from("usage"), chatContext.toBuffer(), payer.publicKey.toBuffer()],
+    llmProgramAddress
+  );
+  const [config] = PublicKey.findProgramAddressSync(
+    [Buffer.from("config")],
+    llmProgramAddress
+  );
+
   it("Is initialized!", async () => {
     const tx = await program.methods
       .initialize(seed)
       .accounts({
         chatContext,
+        config,
         signer: provider.wallet.publicKey,
       })
       .rpc();
@@ -62,6 +73,8 @@ describe("defi-score-agent-example", () => {
       .accounts({
         inference,
         chatContext,
+        usage,
+        config,
         user: provider.wallet.publicKey,
       })
       .rpc();
@@ -75,6 +75,7 @@ describe.skip("solana-llm-oracle", () => {
       .createChat("You're a nice assistant", seed)
       .accountsPartial({
         user: payer.publicKey,
+        payer: payer.publicKey,
         chatContext,
         systemProgram,
       })
@@ -93,11 +94,13 @@ describe.skip("solana-llm-oracle", () => {
         "give me an u8 random number, NOTHING ELSE!!",
         programId,
         callbackDiscriminator,
+        null,
         null
       )
       .accountsPartial({
         chatContext,
         user: payer.publicKey,
+        payer: payer.publicKey,
         inference,
         systemProgram,
       })
@@ -117,11 +120,13 @@ describe.skip("solana-llm-oracle", () => {
         "ur fav number?",
         programId,
         callbackDiscriminator,
+        null,
         null
       )
       .accountsPartial({
         chatContext,
         user: payer.publicKey,
+        payer: payer.publicKey,
         inference,
         systemProgram,
       })
@@ -161,7 +166,7 @@ describe.skip("solana-llm-oracle", () => {
     const chatContext = await getChatContext(seed);
     const inference = await getInferencePda(chatContext);
     const tx = await program.methods
-      .callbackFromLlm("I'm good ser, gm!")
+      .callbackFromLlm(Buffer.from("I'm good ser, gm!"), "gemini-2.5-flash")
       .accountsPartial({
         config,
         inference,
@@ -177,7 +182,7 @@ describe.skip("solana-llm-oracle", () => {
     const chatContext = await getChatContext(seed);
     const inference = await getInferencePda(chatContext);
     const tx = await program.methods
-      .callbackFromLlm("I'm good ser, gm!")
+      .callbackFromLlm(Buffer.from("I'm good ser, gm!"), "gemini-2.5-flash")
       .accountsPartial({
         config,
         inference,